@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS `contest_results` (
+    `atcoder_account_name` VARCHAR(100) NOT NULL,
+    `contest_type` VARCHAR(16) NOT NULL,
+    `contest_screen_name` VARCHAR(100) NOT NULL,
+    `contest_name` VARCHAR(255) NOT NULL,
+    `is_rated` BOOLEAN NOT NULL,
+    `place` INT NOT NULL,
+    `old_rating` INT NOT NULL,
+    `new_rating` INT NOT NULL,
+    `performance` INT NOT NULL,
+    `end_time` DATETIME NOT NULL,
+    PRIMARY KEY (`atcoder_account_name`, `contest_type`, `contest_screen_name`),
+    INDEX `idx_contest_results_contest_screen_name` (`contest_screen_name`)
+);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS `rating_snapshots` (
+    `trap_account_name` VARCHAR(100) NOT NULL,
+    `taken_at` DATETIME NOT NULL,
+    `atcoder_rating` INT,
+    `heuristic_rating` INT,
+    PRIMARY KEY (`trap_account_name`, `taken_at`)
+);
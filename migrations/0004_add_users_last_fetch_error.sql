@@ -0,0 +1 @@
+ALTER TABLE `users` ADD COLUMN `last_fetch_error` VARCHAR(1024);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS `update_runs` (
+    `id` BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    `mode` VARCHAR(16) NOT NULL,
+    `started_at` DATETIME NOT NULL,
+    `finished_at` DATETIME NOT NULL,
+    INDEX `idx_update_runs_started_at` (`started_at`)
+);
@@ -0,0 +1 @@
+ALTER TABLE `update_runs` ADD COLUMN `digest_posted_at` DATETIME;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS `contests` (
+    `contest_id` VARCHAR(100) NOT NULL PRIMARY KEY,
+    `contest_name` VARCHAR(255) NOT NULL,
+    `contest_url` VARCHAR(255) NOT NULL,
+    `start_time` DATETIME NOT NULL,
+    `end_time` DATETIME NOT NULL,
+    `duration_minutes` INT NOT NULL,
+    INDEX `idx_contests_start_time` (`start_time`)
+);
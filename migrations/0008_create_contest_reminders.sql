@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS `contest_reminders` (
+    `contest_id` VARCHAR(100) NOT NULL,
+    `offset_minutes` BIGINT NOT NULL,
+    `posted_at` DATETIME NOT NULL,
+    PRIMARY KEY (`contest_id`, `offset_minutes`)
+);
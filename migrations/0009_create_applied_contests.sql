@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS `applied_contests` (
+    `contest_id` VARCHAR(100) NOT NULL PRIMARY KEY,
+    `contest_type` VARCHAR(16) NOT NULL,
+    `end_time` DATETIME NOT NULL,
+    -- NULL until the results are out
+    `applied_at` DATETIME NULL,
+    INDEX `idx_applied_contests_end_time` (`end_time`)
+);
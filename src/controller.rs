@@ -1,2 +1,16 @@
+pub mod api_error;
 pub mod get_users_handler;
-pub mod get_rate_handler;
\ No newline at end of file
+pub mod get_rate_handler;
+pub mod bot_handler;
+pub mod get_contests_handler;
+pub mod get_history_handler;
+pub mod get_ratings_handler;
+pub mod get_stats_handler;
+pub mod get_badge_handler;
+pub mod get_graph_handler;
+pub mod get_compare_handler;
+pub mod svg_response;
+pub mod metrics_handler;
+pub mod health_handler;
+pub mod openapi;
+pub mod router;
\ No newline at end of file
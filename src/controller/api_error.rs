@@ -0,0 +1,87 @@
+use axum::{
+    extract::rejection::QueryRejection,
+    response::{IntoResponse, Response},
+    Json,
+};
+use reqwest::StatusCode;
+
+/// An error response of the API, serialized as `{"code": ..., "message": ...}`.
+///
+/// `code` is stable for clients to branch on, `message` is for humans.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+#[schema(as = ApiError)]
+pub struct ApiErrorBody {
+    /// A stable identifier of the error.
+    #[schema(example = "user_not_found")]
+    code: &'static str,
+    /// A human-readable description of the error.
+    #[schema(example = "User comavius is not known")]
+    message: String,
+}
+
+impl ApiError {
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            code: "bad_request",
+            message: message.into(),
+        }
+    }
+
+    pub fn user_not_found(trap_account_name: &str) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            code: "user_not_found",
+            message: format!("User {} is not known", trap_account_name),
+        }
+    }
+
+    /// The user exists but has not linked an AtCoder account on traPortfolio.
+    pub fn atcoder_not_linked(trap_account_name: &str) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            code: "atcoder_not_linked",
+            message: format!("User {} has no linked AtCoder account", trap_account_name),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            code: "not_found",
+            message: message.into(),
+        }
+    }
+
+    /// The database or an upstream API failed. The cause is logged, not returned.
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: "internal_error",
+            message: message.into(),
+        }
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        Self::bad_request(rejection.body_text())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ApiErrorBody {
+            code: self.code,
+            message: self.message,
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
@@ -0,0 +1,50 @@
+use axum::{
+    body::Bytes,
+    extract::Extension,
+    http::HeaderMap,
+};
+use reqwest::StatusCode;
+use std::sync::Arc;
+use traq_bot_http::{ErrorKind, Event, RequestParser};
+use crate::usecase::bot::Bot;
+
+/// Receives traQ bot events. The `X-TRAQ-BOT-TOKEN` header is checked against the verification token.
+pub async fn handler<PR, TB>(
+    Extension(parser): Extension<Arc<RequestParser>>,
+    Extension(bot): Extension<Arc<Bot<PR, TB>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+    TB: crate::domain::traq_bot::TraqBot,
+{
+    let event = match parser.parse(headers.iter(), &body) {
+        Ok(event) => event,
+        Err(e) => {
+            tracing::warn!("Rejected bot event: {}", e);
+            return match e.kind() {
+                ErrorKind::BotTokenNotFound | ErrorKind::BotTokenMismatch => StatusCode::UNAUTHORIZED,
+                _ => StatusCode::BAD_REQUEST,
+            };
+        }
+    };
+    let (message, is_direct) = match event {
+        Event::MessageCreated(payload) => (payload.message, false),
+        Event::DirectMessageCreated(payload) => (payload.message, true),
+        _ => return StatusCode::NO_CONTENT,
+    };
+    if message.user.bot {
+        return StatusCode::NO_CONTENT;
+    }
+    // traQ gives up on slow responses, so reply in the background
+    tokio::spawn(async move {
+        if let Err(e) = bot
+            .handle_message(&message.channel_id, &message.plain_text, is_direct)
+            .await
+        {
+            tracing::error!("Failed to handle bot command: {}", e);
+        }
+    });
+    StatusCode::NO_CONTENT
+}
@@ -0,0 +1,75 @@
+use axum::{
+    extract::{Extension, Query, rejection::QueryRejection},
+    http::HeaderMap,
+    response::Response,
+};
+use std::sync::Arc;
+use crate::domain::entity::ContestType;
+use crate::usecase::badge::{BadgeStyle, default_label, render_rating_badge};
+use super::api_error::{ApiError, ApiErrorBody};
+use super::svg_response::cached_svg;
+
+// Ratings change at most once per update run, so an hour of staleness is fine
+const BADGE_MAX_AGE_SECS: u64 = 3600;
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BadgeQuery {
+    /// The text on the left, defaults to `AtCoder` or `AtCoder Heuristic`.
+    label: Option<String>,
+    /// `flat` (default) or `flat-square`.
+    style: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/badge/{contestType}/{file}",
+    operation_id = "getRatingBadge",
+    tag = "Ratings",
+    summary = "Get a rating badge of a user",
+    description = "Returns a shields-style SVG badge showing the rating in its AtCoder color, for embedding in profiles.",
+    params(
+        ("contestType" = String, Path, description = "`algorithm` or `heuristic`"),
+        ("file" = String, Path, description = "The trap account name of the user followed by `.svg`", example = "comavius.svg"),
+        BadgeQuery,
+    ),
+    responses(
+        (status = 200, description = "The badge", content_type = "image/svg+xml", body = String, headers(
+            ("Cache-Control" = String),
+            ("ETag" = String),
+        )),
+        (status = 304, description = "The badge has not changed since the given `If-None-Match`"),
+        (status = 400, description = "The type or style is invalid", body = ApiErrorBody),
+        (status = 404, description = "The user is not known (`user_not_found`) or has not linked an AtCoder account (`atcoder_not_linked`)", body = ApiErrorBody),
+        (status = 500, description = "The database failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn handler<PR>(
+    axum::extract::Path((contest_type, file)): axum::extract::Path<(String, String)>,
+    query: Result<Query<BadgeQuery>, QueryRejection>,
+    headers: HeaderMap,
+    Extension(p_repo): Extension<Arc<PR>>,
+) -> Result<Response, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    tracing::info!("Received request for {} badge: {}", contest_type, file);
+    let Query(query) = query?;
+    let contest_type = contest_type
+        .parse::<ContestType>()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let style = query.style
+        .as_deref()
+        .map_or(Ok(BadgeStyle::default()), str::parse::<BadgeStyle>)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let trap_account_name = file
+        .strip_suffix(".svg")
+        .ok_or_else(|| ApiError::not_found(format!("{} is not an SVG file", file)))?;
+    let user = super::get_rate_handler::get_linked_user(p_repo.as_ref(), trap_account_name).await?;
+    let label = query.label
+        .as_deref()
+        .unwrap_or(default_label(contest_type));
+    let svg = render_rating_badge(label, user.rating(contest_type), style);
+    tracing::info!("Returning {} badge for account name: {}", contest_type.as_str(), trap_account_name);
+    Ok(cached_svg(svg, &headers, BADGE_MAX_AGE_SECS))
+}
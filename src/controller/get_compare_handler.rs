@@ -0,0 +1,156 @@
+use axum::{
+    extract::{Extension, Query, rejection::QueryRejection},
+    http::HeaderMap,
+    response::{IntoResponse, Response},
+    Json,
+};
+use reqwest::StatusCode;
+use std::sync::Arc;
+use crate::domain::dto::User;
+use crate::domain::entity::{ContestResult, ContestType};
+use crate::usecase::compare::compare;
+use crate::usecase::rating_graph::{GraphSeries, render_rating_graph};
+use super::api_error::{ApiError, ApiErrorBody};
+use super::get_graph_handler::graph_size;
+use super::svg_response::cached_svg;
+
+// As many as the graph has line colors for
+const MAX_USERS: usize = 6;
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CompareQuery {
+    /// From 2 to 6 comma-separated trap account names.
+    #[param(example = "alice,bob")]
+    users: String,
+    /// `algorithm` or `heuristic`, defaults to `algorithm`.
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    contest_type: Option<String>,
+    /// The width of the SVG in pixels, from 200 to 2000. Defaults to 640.
+    #[param(minimum = 200, maximum = 2000)]
+    width: Option<u32>,
+    /// The height of the SVG in pixels, from 120 to 1200. Defaults to 360.
+    #[param(minimum = 120, maximum = 1200)]
+    height: Option<u32>,
+}
+
+impl CompareQuery {
+    fn contest_type(&self) -> Result<ContestType, ApiError> {
+        self.contest_type
+            .as_deref()
+            .map_or(Ok(ContestType::Algorithm), str::parse::<ContestType>)
+            .map_err(|e| ApiError::bad_request(e.to_string()))
+    }
+
+    /// The distinct names in the order they were given.
+    fn users(&self) -> Result<Vec<&str>, ApiError> {
+        let mut names = Vec::<&str>::new();
+        for name in self.users.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        if !(2..=MAX_USERS).contains(&names.len()) {
+            return Err(ApiError::bad_request(format!("users must name from 2 to {} distinct users", MAX_USERS)));
+        }
+        Ok(names)
+    }
+}
+
+async fn get_accounts<PR>(
+    p_repo: &PR,
+    names: &[&str],
+    contest_type: ContestType,
+) -> Result<Vec<(User, Vec<ContestResult>)>, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    let mut accounts = vec![];
+    for name in names {
+        let user = super::get_rate_handler::get_linked_user(p_repo, name).await?;
+        let results = p_repo
+            .get_contest_results(user.atcoder_account_name.as_deref().unwrap_or_default(), contest_type)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get contest results: {}", e);
+                ApiError::internal("Failed to get contest results")
+            })?;
+        accounts.push((user, results));
+    }
+    Ok(accounts)
+}
+
+#[utoipa::path(
+    get,
+    path = "/compare",
+    operation_id = "compareUsers",
+    tag = "Users",
+    summary = "Compare the ratings of several users",
+    description = "Returns the rating timelines of the users side by side, with the contests at least two of them entered and their win counts against each other. A lower place wins.",
+    params(CompareQuery),
+    responses(
+        (status = 200, description = "The comparison", body = crate::domain::dto::Comparison),
+        (status = 400, description = "The query parameters are invalid", body = ApiErrorBody),
+        (status = 404, description = "A user is not known (`user_not_found`) or has not linked an AtCoder account (`atcoder_not_linked`)", body = ApiErrorBody),
+        (status = 500, description = "The database failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn handler<PR>(
+    query: Result<Query<CompareQuery>, QueryRejection>,
+    Extension(p_repo): Extension<Arc<PR>>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    let Query(query) = query?;
+    tracing::info!("Received request to compare users: {}", query.users);
+    let contest_type = query.contest_type()?;
+    let names = query.users()?;
+    let accounts = get_accounts(p_repo.as_ref(), &names, contest_type).await?;
+    let comparison = compare(contest_type, accounts);
+    tracing::info!("Returning comparison with {} shared contests", comparison.head_to_head.len());
+    Ok((StatusCode::OK, Json(comparison)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/compare.svg",
+    operation_id = "getComparisonGraph",
+    tag = "Users",
+    summary = "Get the rating graphs of several users overlaid",
+    description = "Returns the rating histories as one SVG chart over AtCoder's color bands, with a line and a legend entry per user.",
+    params(CompareQuery),
+    responses(
+        (status = 200, description = "The graph", content_type = "image/svg+xml", body = String, headers(
+            ("Cache-Control" = String),
+            ("ETag" = String),
+        )),
+        (status = 304, description = "The graph has not changed since the given `If-None-Match`"),
+        (status = 400, description = "The query parameters are invalid", body = ApiErrorBody),
+        (status = 404, description = "A user is not known (`user_not_found`) or has not linked an AtCoder account (`atcoder_not_linked`)", body = ApiErrorBody),
+        (status = 500, description = "The database failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn svg_handler<PR>(
+    query: Result<Query<CompareQuery>, QueryRejection>,
+    headers: HeaderMap,
+    Extension(p_repo): Extension<Arc<PR>>,
+) -> Result<Response, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    let Query(query) = query?;
+    tracing::info!("Received request for comparison graph of users: {}", query.users);
+    let contest_type = query.contest_type()?;
+    let names = query.users()?;
+    let (width, height) = graph_size(query.width, query.height)?;
+    let series = get_accounts(p_repo.as_ref(), &names, contest_type)
+        .await?
+        .into_iter()
+        .map(|(user, results)| GraphSeries::from_results(user.trap_account_name, &results))
+        .collect::<Vec<_>>();
+    let svg = render_rating_graph(&series, width, height);
+    tracing::info!("Returning comparison graph of {} users", series.len());
+    Ok(cached_svg(svg, &headers, super::get_graph_handler::GRAPH_MAX_AGE_SECS))
+}
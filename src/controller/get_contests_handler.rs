@@ -0,0 +1,71 @@
+use axum::{
+    extract::Extension,
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use std::sync::Arc;
+use super::api_error::{ApiError, ApiErrorBody};
+
+#[utoipa::path(
+    get,
+    path = "/contests/upcoming",
+    operation_id = "getUpcomingContests",
+    tag = "Contests",
+    summary = "Get the upcoming contests",
+    description = "Returns the upcoming ABC, ARC, AGC and AHC, soonest first.",
+    responses(
+        (status = 200, description = "A list of upcoming contests", body = Vec<crate::domain::dto::ScheduledContest>),
+        (status = 500, description = "The database failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn upcoming_handler<PR>(
+    Extension(p_repo): Extension<Arc<PR>>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    tracing::info!("Received request to get upcoming contests");
+    let contests = p_repo
+        .get_contests_starting_after(chrono::Utc::now())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get upcoming contests: {}", e);
+            ApiError::internal("Failed to get upcoming contests")
+        })?;
+    tracing::info!("Successfully fetched upcoming contests");
+    Ok((StatusCode::OK, Json(contests)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/contests/{screenName}/results",
+    operation_id = "getContestResults",
+    tag = "Contests",
+    summary = "Get how traP members did in a contest",
+    description = "Returns the places, performances and rating changes of the members who took part in a contest.",
+    params(("screenName" = String, Path, description = "The screen name of the contest, either `abc400` or `abc400.contest.atcoder.jp`")),
+    responses(
+        (status = 200, description = "The results of the members", body = crate::domain::dto::ContestReport),
+        (status = 404, description = "No member took part in the contest", body = ApiErrorBody),
+        (status = 500, description = "The database failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn results_handler<PR>(
+    axum::extract::Path(screen_name): axum::extract::Path<String>,
+    Extension(p_repo): Extension<Arc<PR>>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    tracing::info!("Received request for results of contest: {}", screen_name);
+    let report = crate::usecase::contest_report::get_contest_report(p_repo.as_ref(), &screen_name)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get contest report: {}", e);
+            ApiError::internal("Failed to get contest report")
+        })?
+        .ok_or_else(|| ApiError::not_found(format!("No member took part in {}", screen_name)))?;
+    tracing::info!("Returning results of contest: {}", screen_name);
+    Ok((StatusCode::OK, Json(report)))
+}
@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Extension, Query, rejection::QueryRejection},
+    http::HeaderMap,
+    response::Response,
+};
+use std::sync::Arc;
+use crate::domain::entity::ContestType;
+use crate::usecase::rating_graph::{GraphSeries, render_rating_graph};
+use super::api_error::{ApiError, ApiErrorBody};
+use super::svg_response::cached_svg;
+
+pub(super) const GRAPH_MAX_AGE_SECS: u64 = 3600;
+const DEFAULT_SIZE: (u32, u32) = (640, 360);
+const MIN_SIZE: (u32, u32) = (200, 120);
+const MAX_SIZE: (u32, u32) = (2000, 1200);
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GraphQuery {
+    /// `algorithm` or `heuristic`, defaults to `algorithm`.
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    contest_type: Option<String>,
+    /// The width in pixels, from 200 to 2000. Defaults to 640.
+    #[param(minimum = 200, maximum = 2000)]
+    width: Option<u32>,
+    /// The height in pixels, from 120 to 1200. Defaults to 360.
+    #[param(minimum = 120, maximum = 1200)]
+    height: Option<u32>,
+}
+
+/// Applies the default size and checks the bounds.
+pub(super) fn graph_size(width: Option<u32>, height: Option<u32>) -> Result<(u32, u32), ApiError> {
+    let width = width.unwrap_or(DEFAULT_SIZE.0);
+    let height = height.unwrap_or(DEFAULT_SIZE.1);
+    if !(MIN_SIZE.0..=MAX_SIZE.0).contains(&width) || !(MIN_SIZE.1..=MAX_SIZE.1).contains(&height) {
+        return Err(ApiError::bad_request(format!(
+            "The size must be between {}x{} and {}x{}",
+            MIN_SIZE.0, MIN_SIZE.1, MAX_SIZE.0, MAX_SIZE.1,
+        )));
+    }
+    Ok((width, height))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{trapAccountName}/graph.svg",
+    operation_id = "getRatingGraph",
+    tag = "Users",
+    summary = "Get the rating graph of a user",
+    description = "Returns the rating history as an SVG chart over AtCoder's color bands, with a point per rated contest.",
+    params(
+        ("trapAccountName" = String, Path, description = "The trap account name of the user"),
+        GraphQuery,
+    ),
+    responses(
+        (status = 200, description = "The graph", content_type = "image/svg+xml", body = String, headers(
+            ("Cache-Control" = String),
+            ("ETag" = String),
+        )),
+        (status = 304, description = "The graph has not changed since the given `If-None-Match`"),
+        (status = 400, description = "The type or size is invalid", body = ApiErrorBody),
+        (status = 404, description = "The user is not known (`user_not_found`) or has not linked an AtCoder account (`atcoder_not_linked`)", body = ApiErrorBody),
+        (status = 500, description = "The database failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn handler<PR>(
+    axum::extract::Path(trap_account_name): axum::extract::Path<String>,
+    query: Result<Query<GraphQuery>, QueryRejection>,
+    headers: HeaderMap,
+    Extension(p_repo): Extension<Arc<PR>>,
+) -> Result<Response, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    tracing::info!("Received request for graph with account name: {}", trap_account_name);
+    let Query(query) = query?;
+    let contest_type = query.contest_type
+        .as_deref()
+        .map_or(Ok(ContestType::Algorithm), str::parse::<ContestType>)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let (width, height) = graph_size(query.width, query.height)?;
+    let user = super::get_rate_handler::get_linked_user(p_repo.as_ref(), &trap_account_name).await?;
+    let results = p_repo
+        .get_contest_results(user.atcoder_account_name.as_deref().unwrap_or_default(), contest_type)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get contest results: {}", e);
+            ApiError::internal("Failed to get contest results")
+        })?;
+    let series = GraphSeries::from_results(trap_account_name.clone(), &results);
+    let svg = render_rating_graph(&[series], width, height);
+    tracing::info!("Returning graph of {} contests for account name: {}", results.len(), trap_account_name);
+    Ok(cached_svg(svg, &headers, GRAPH_MAX_AGE_SECS))
+}
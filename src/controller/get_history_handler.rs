@@ -0,0 +1,80 @@
+use axum::{
+    extract::{Extension, Query, rejection::QueryRejection},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use std::sync::Arc;
+use crate::domain::dto::ContestHistoryEntry;
+use crate::domain::entity::ContestType;
+use super::api_error::{ApiError, ApiErrorBody};
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQuery {
+    /// `algorithm` or `heuristic`, defaults to `algorithm`.
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    contest_type: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{trapAccountName}/history",
+    operation_id = "getUserHistory",
+    tag = "Users",
+    summary = "Get the contest history of a user",
+    description = "Returns every contest the user took part in, oldest first. Users without an AtCoder account have an empty history.",
+    params(
+        ("trapAccountName" = String, Path, description = "The trap account name of the user"),
+        HistoryQuery,
+    ),
+    responses(
+        (status = 200, description = "The contest history of the user", body = Vec<ContestHistoryEntry>),
+        (status = 400, description = "The type is neither algorithm nor heuristic", body = ApiErrorBody),
+        (status = 404, description = "The user is not known", body = ApiErrorBody),
+        (status = 500, description = "The database failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn handler<PR>(
+    axum::extract::Path(trap_account_name): axum::extract::Path<String>,
+    query: Result<Query<HistoryQuery>, QueryRejection>,
+    Extension(p_repo): Extension<Arc<PR>>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    tracing::info!("Received request for history with account name: {}", trap_account_name);
+    let Query(query) = query?;
+    let contest_type = query.contest_type
+        .as_deref()
+        .map_or(Ok(ContestType::Algorithm), str::parse::<ContestType>)
+        .map_err(|e| {
+            tracing::warn!("Invalid history query: {}", e);
+            ApiError::bad_request(e.to_string())
+        })?;
+    let user = p_repo
+        .get_user(&trap_account_name)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get user: {}", e);
+            ApiError::internal("Failed to get user")
+        })?
+        .ok_or_else(|| ApiError::user_not_found(&trap_account_name))?;
+    let history = match &user.atcoder_account_name {
+        Some(atcoder_account_name) => p_repo
+            .get_contest_results(atcoder_account_name, contest_type)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get contest results: {}", e);
+                ApiError::internal("Failed to get contest results")
+            })?,
+        None => vec![],
+    };
+    let history = history
+        .into_iter()
+        .map(ContestHistoryEntry::from)
+        .collect::<Vec<_>>();
+    tracing::info!("Returning {} history entries for account name: {}", history.len(), trap_account_name);
+    Ok((StatusCode::OK, Json(history)))
+}
@@ -1,51 +1,146 @@
 use axum::{
-    extract::Extension,
+    extract::{Extension, Query, rejection::QueryRejection},
     response::IntoResponse,
     Json,
 };
 use reqwest::StatusCode;
 use std::sync::Arc;
+use crate::domain::dto::User;
+use crate::domain::entity::ContestType;
+use crate::domain::rating_color::RatingTier;
+use super::api_error::{ApiError, ApiErrorBody};
 
-pub async fn heur_handler<PR>(
-    axum::extract::Path(trap_account_name): axum::extract::Path<String>,
-    Extension(p_repo): Extension<Arc<PR>>,
-) -> Result<impl IntoResponse, StatusCode> 
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RateQuery {
+    /// Return the rating with its color tier instead of a bare number.
+    details: Option<bool>,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct RatingDetails {
+    /// The rating, or null if it has not been fetched yet.
+    #[schema(example = 1866)]
+    rating: Option<i32>,
+    /// The color of the rating, or null if unrated.
+    tier: Option<RatingTier>,
+    #[serde(rename = "ratedContests")]
+    #[schema(example = 25)]
+    rated_contests: i64,
+}
+
+/// A bare rating unless `details` is requested.
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+#[serde(untagged)]
+pub enum RateResponse {
+    Rating(Option<i32>),
+    Details(RatingDetails),
+}
+
+/// Looks up a user who has linked an AtCoder account.
+pub(super) async fn get_linked_user<PR>(p_repo: &PR, trap_account_name: &str) -> Result<User, ApiError>
 where
     PR: crate::domain::persist_repository::PersistRepository,
 {
-    tracing::info!("Received request for heuristic rate with account name: {}", trap_account_name);
     let user = p_repo
-        .get_user(&trap_account_name)
+        .get_user(trap_account_name)
         .await
         .map_err(|e| {
             tracing::error!("Failed to get user: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
-    let rate = user
-        .map(|u| u.heuristic_rating)
-        .flatten();
+            ApiError::internal("Failed to get user")
+        })?
+        .ok_or_else(|| ApiError::user_not_found(trap_account_name))?;
+    if user.atcoder_account_name.is_none() {
+        return Err(ApiError::atcoder_not_linked(trap_account_name));
+    }
+    Ok(user)
+}
+
+async fn get_rate<PR>(
+    p_repo: &PR,
+    trap_account_name: &str,
+    contest_type: ContestType,
+    query: Result<Query<RateQuery>, QueryRejection>,
+) -> Result<RateResponse, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    let Query(query) = query?;
+    let user = get_linked_user(p_repo, trap_account_name).await?;
+    let rating = user.rating(contest_type);
+    if !query.details.unwrap_or(false) {
+        return Ok(RateResponse::Rating(rating));
+    }
+    let atcoder_account_name = user.atcoder_account_name.unwrap_or_default();
+    let rated_contests = p_repo
+        .get_contest_results(&atcoder_account_name, contest_type)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get contest results: {}", e);
+            ApiError::internal("Failed to get contest results")
+        })?
+        .iter()
+        .filter(|result| result.is_rated)
+        .count() as i64;
+    Ok(RateResponse::Details(RatingDetails {
+        rating,
+        tier: rating.and_then(|rating| RatingTier::new(rating, rated_contests)),
+        rated_contests,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/rate/heuristic/{trapAccountName}",
+    operation_id = "getHeuristicRating",
+    tag = "Ratings",
+    summary = "Get the heuristic rating of a user",
+    params(("trapAccountName" = String, Path, description = "The trap account name of the user"), RateQuery),
+    responses(
+        (status = 200, description = "The heuristic rating, or null if it has not been fetched yet", body = RateResponse, example = 1854),
+        (status = 400, description = "The query parameters are invalid", body = ApiErrorBody),
+        (status = 404, description = "The user is not known (`user_not_found`) or has not linked an AtCoder account (`atcoder_not_linked`)", body = ApiErrorBody),
+        (status = 500, description = "The database failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn heur_handler<PR>(
+    axum::extract::Path(trap_account_name): axum::extract::Path<String>,
+    query: Result<Query<RateQuery>, QueryRejection>,
+    Extension(p_repo): Extension<Arc<PR>>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    tracing::info!("Received request for heuristic rate with account name: {}", trap_account_name);
+    let rate = get_rate(p_repo.as_ref(), &trap_account_name, ContestType::Heuristic, query).await?;
     tracing::info!("Returning heuristic rate for account name: {}", trap_account_name);
     Ok((StatusCode::OK, Json(rate)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/rate/algorithm/{trapAccountName}",
+    operation_id = "getAlgorithmRating",
+    tag = "Ratings",
+    summary = "Get the algorithm rating of a user",
+    params(("trapAccountName" = String, Path, description = "The trap account name of the user"), RateQuery),
+    responses(
+        (status = 200, description = "The algorithm rating, or null if it has not been fetched yet", body = RateResponse, example = 1866),
+        (status = 400, description = "The query parameters are invalid", body = ApiErrorBody),
+        (status = 404, description = "The user is not known (`user_not_found`) or has not linked an AtCoder account (`atcoder_not_linked`)", body = ApiErrorBody),
+        (status = 500, description = "The database failed", body = ApiErrorBody),
+    ),
+)]
 pub async fn algo_handler<PR>(
     axum::extract::Path(trap_account_name): axum::extract::Path<String>,
+    query: Result<Query<RateQuery>, QueryRejection>,
     Extension(p_repo): Extension<Arc<PR>>,
-) -> Result<impl IntoResponse, StatusCode> 
+) -> Result<impl IntoResponse, ApiError>
 where
     PR: crate::domain::persist_repository::PersistRepository,
 {
     tracing::info!("Received request for algorithmic rate with account name: {}", trap_account_name);
-    let user = p_repo
-        .get_user(&trap_account_name)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to get user: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
-    let rate = user
-        .map(|u| u.atcoder_rating)
-        .flatten();
+    let rate = get_rate(p_repo.as_ref(), &trap_account_name, ContestType::Algorithm, query).await?;
     tracing::info!("Returning algorithmic rate for account name: {}", trap_account_name);
     Ok((StatusCode::OK, Json(rate)))
 }
@@ -0,0 +1,71 @@
+use axum::{
+    extract::{Extension, Query, rejection::QueryRejection},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use std::sync::Arc;
+use crate::domain::dto::RatingSnapshot;
+use super::api_error::{ApiError, ApiErrorBody};
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RatingsQuery {
+    /// RFC 3339 timestamp, defaults to the Unix epoch.
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    /// RFC 3339 timestamp, defaults to now.
+    to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{trapAccountName}/ratings",
+    operation_id = "getUserRatings",
+    tag = "Users",
+    summary = "Get the rating snapshots of a user",
+    description = "Returns the ratings of the user recorded by the update runs between `from` and `to` inclusive, oldest first.",
+    params(
+        ("trapAccountName" = String, Path, description = "The trap account name of the user"),
+        RatingsQuery,
+    ),
+    responses(
+        (status = 200, description = "The rating snapshots of the user", body = Vec<RatingSnapshot>),
+        (status = 400, description = "A timestamp is malformed or `from` is after `to`", body = ApiErrorBody),
+        (status = 404, description = "The user is not known", body = ApiErrorBody),
+        (status = 500, description = "The database failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn handler<PR>(
+    axum::extract::Path(trap_account_name): axum::extract::Path<String>,
+    query: Result<Query<RatingsQuery>, QueryRejection>,
+    Extension(p_repo): Extension<Arc<PR>>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    tracing::info!("Received request for ratings with account name: {}", trap_account_name);
+    let Query(query) = query?;
+    let from = query.from.unwrap_or(chrono::DateTime::UNIX_EPOCH);
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    if from > to {
+        tracing::warn!("Invalid ratings query: {} is after {}", from, to);
+        return Err(ApiError::bad_request("from must not be after to"));
+    }
+    p_repo
+        .get_user(&trap_account_name)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get user: {}", e);
+            ApiError::internal("Failed to get user")
+        })?
+        .ok_or_else(|| ApiError::user_not_found(&trap_account_name))?;
+    let snapshots = p_repo
+        .get_rating_snapshots(&trap_account_name, from, to)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get rating snapshots: {}", e);
+            ApiError::internal("Failed to get rating snapshots")
+        })?;
+    tracing::info!("Returning {} rating snapshots for account name: {}", snapshots.len(), trap_account_name);
+    Ok((StatusCode::OK, Json(snapshots)))
+}
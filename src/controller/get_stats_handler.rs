@@ -0,0 +1,58 @@
+use axum::{
+    extract::{Extension, Query, rejection::QueryRejection},
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use std::sync::Arc;
+use crate::domain::entity::ContestType;
+use super::api_error::{ApiError, ApiErrorBody};
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    /// `algorithm` or `heuristic`, defaults to `algorithm`.
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    contest_type: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats",
+    operation_id = "getStats",
+    tag = "Stats",
+    summary = "Get statistics of the club",
+    description = "Returns the color distribution and rating aggregates per grade and for the algorithm team. Only active members are counted.",
+    params(StatsQuery),
+    responses(
+        (status = 200, description = "The statistics of the club", body = crate::domain::dto::ClubStats),
+        (status = 400, description = "The type is neither algorithm nor heuristic", body = ApiErrorBody),
+        (status = 500, description = "The database failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn handler<PR>(
+    query: Result<Query<StatsQuery>, QueryRejection>,
+    Extension(p_repo): Extension<Arc<PR>>,
+) -> Result<impl IntoResponse, ApiError>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    tracing::info!("Received request for stats");
+    let Query(query) = query?;
+    let contest_type = query.contest_type
+        .as_deref()
+        .map_or(Ok(ContestType::Algorithm), str::parse::<ContestType>)
+        .map_err(|e| {
+            tracing::warn!("Invalid stats query: {}", e);
+            ApiError::bad_request(e.to_string())
+        })?;
+    let stats = crate::usecase::stats::get_club_stats(p_repo.as_ref(), contest_type)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get stats: {}", e);
+            ApiError::internal("Failed to get stats")
+        })?;
+    tracing::info!("Returning stats of {} members", stats.members);
+    Ok((StatusCode::OK, Json(stats)))
+}
@@ -1,25 +1,128 @@
 use axum::{
-    extract::Extension,
+    extract::{Extension, Query, rejection::QueryRejection},
     response::IntoResponse,
     Json,
 };
 use reqwest::StatusCode;
 use std::sync::Arc;
+use crate::domain::dto::UserResponse;
+use crate::domain::entity::{UserQuery, UserSort};
+use super::api_error::ApiError;
 
+const MAX_LIMIT: u32 = 1000;
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct UsersQuery {
+    /// Only users of this grade, e.g. 23B.
+    grade: Option<String>,
+    /// Only members (true) or non-members (false) of the algorithm team.
+    algo_team: Option<bool>,
+    /// Only active (true) or inactive (false) users.
+    active: Option<bool>,
+    /// Only users with (true) or without (false) a linked AtCoder account.
+    has_atcoder: Option<bool>,
+    /// Only users whose algorithm rating is at least this.
+    min_rating: Option<i32>,
+    /// Only users whose heuristic rating is at least this.
+    min_heuristic_rating: Option<i32>,
+    /// The order as `key[:asc|desc]` with key one of trapAccountName, atcoderRating, heuristicRating
+    /// and grade. Users without a value come last, ties are ordered by trap account name.
+    #[param(example = "atcoderRating:desc")]
+    sort: Option<String>,
+    /// The maximum number of users to return, at most 1000.
+    #[param(maximum = 1000)]
+    limit: Option<u32>,
+    /// The number of matching users to skip.
+    offset: Option<u32>,
+}
+
+impl TryFrom<UsersQuery> for UserQuery {
+    type Error = anyhow::Error;
+
+    fn try_from(query: UsersQuery) -> Result<Self, Self::Error> {
+        if query.limit.is_some_and(|limit| limit > MAX_LIMIT) {
+            return Err(anyhow::anyhow!("limit must be at most {}", MAX_LIMIT));
+        }
+        let sort = query.sort
+            .as_deref()
+            .map(str::parse::<UserSort>)
+            .transpose()?;
+        Ok(UserQuery {
+            grade: query.grade,
+            is_algo_team: query.algo_team,
+            is_active: query.active,
+            has_atcoder: query.has_atcoder,
+            min_atcoder_rating: query.min_rating,
+            min_heuristic_rating: query.min_heuristic_rating,
+            sort,
+            limit: query.limit,
+            offset: query.offset.unwrap_or(0),
+        })
+    }
+}
+
+/// Lists users matching the query. The number of matches before paging is in `X-Total-Count`.
+#[utoipa::path(
+    get,
+    path = "/users",
+    operation_id = "getUsers",
+    tag = "Users",
+    summary = "Get a list of users",
+    description = "Returns the users matching the filters. Without any parameters every user is returned, ordered by trap account name.",
+    params(UsersQuery),
+    responses(
+        (status = 200, description = "A page of users", body = Vec<UserResponse>, headers(
+            ("X-Total-Count" = i64, description = "The number of matching users before limit and offset are applied"),
+        )),
+        (status = 400, description = "The query parameters are invalid", body = super::api_error::ApiErrorBody),
+        (status = 500, description = "The database failed", body = super::api_error::ApiErrorBody),
+    ),
+)]
 pub async fn handler<PR>(
+    query: Result<Query<UsersQuery>, QueryRejection>,
     Extension(p_repo): Extension<Arc<PR>>,
-) -> Result<impl IntoResponse, StatusCode> 
-where 
+) -> Result<impl IntoResponse, ApiError>
+where
     PR: crate::domain::persist_repository::PersistRepository,
 {
     tracing::info!("Received request to get users");
-    let users = p_repo
-        .get_users()
+    let Query(query) = query?;
+    let query = UserQuery::try_from(query).map_err(|e| {
+        tracing::warn!("Invalid users query: {}", e);
+        ApiError::bad_request(e.to_string())
+    })?;
+    let (users, total) = p_repo
+        .find_users(&query)
         .await
         .map_err(|e| {
             tracing::error!("Failed to get users: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
+            ApiError::internal("Failed to get users")
+        })?;
+    let atcoder_account_names = users
+        .iter()
+        .filter_map(|user| user.atcoder_account_name.clone())
+        .collect::<Vec<_>>();
+    let rated_contests = p_repo
+        .get_rated_contest_counts(&atcoder_account_names)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to count rated contests: {}", e);
+            ApiError::internal("Failed to count rated contests")
         })?;
-    tracing::info!("Successfully fetched users");
-    Ok((StatusCode::OK, Json(users)))
+    let users = users
+        .into_iter()
+        .map(|user| {
+            let atcoder_account_name = user.atcoder_account_name.clone().unwrap_or_default();
+            UserResponse::new(user, |contest_type| {
+                rated_contests
+                    .get(&(atcoder_account_name.clone(), contest_type))
+                    .copied()
+                    .unwrap_or(0)
+            })
+        })
+        .collect::<Vec<_>>();
+    tracing::info!("Successfully fetched {} of {} users", users.len(), total);
+    Ok((StatusCode::OK, [("X-Total-Count", total.to_string())], Json(users)))
 }
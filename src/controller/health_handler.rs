@@ -0,0 +1,37 @@
+use axum::{
+    extract::Extension,
+    response::IntoResponse,
+    Json,
+};
+use reqwest::StatusCode;
+use std::sync::Arc;
+use crate::usecase::health::check_readiness;
+
+/// How old the data may get before `/readyz` reports it as stale.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadinessConfig {
+    pub max_data_age: chrono::Duration,
+}
+
+/// Answers as long as the process is serving requests.
+pub async fn healthz_handler() -> impl IntoResponse {
+    (StatusCode::OK, "ok")
+}
+
+/// 200 when the database is reachable, 503 otherwise. Stale data is reported in the body only.
+pub async fn readyz_handler<PR>(
+    Extension(p_repo): Extension<Arc<PR>>,
+    Extension(config): Extension<ReadinessConfig>,
+) -> impl IntoResponse
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    let readiness = check_readiness(p_repo.as_ref(), config.max_data_age, chrono::Utc::now()).await;
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        tracing::warn!("Not ready: database is not reachable");
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness))
+}
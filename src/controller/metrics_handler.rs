@@ -0,0 +1,35 @@
+use axum::{
+    extract::{Extension, MatchedPath, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use metrics_exporter_prometheus::PrometheusHandle;
+use reqwest::{StatusCode, header};
+
+/// Serves every recorded metric in the Prometheus text format.
+pub async fn handler(Extension(handle): Extension<PrometheusHandle>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        handle.render(),
+    )
+}
+
+/// Counts and times requests per route. Mount with `route_layer` so that unmatched
+/// paths do not turn into labels.
+pub async fn track_requests(matched_path: Option<MatchedPath>, request: Request, next: Next) -> Response {
+    let route = matched_path.map_or_else(|| "unmatched".to_string(), |path| path.as_str().to_string());
+    let method = request.method().to_string();
+    let started = std::time::Instant::now();
+    let response = next.run(request).await;
+    metrics::histogram!("http_request_duration_seconds", "method" => method.clone(), "route" => route.clone())
+        .record(started.elapsed().as_secs_f64());
+    metrics::counter!(
+        "http_requests_total",
+        "method" => method,
+        "route" => route,
+        "status" => response.status().as_u16().to_string(),
+    )
+        .increment(1);
+    response
+}
@@ -0,0 +1,42 @@
+use axum::{response::IntoResponse, Json};
+use utoipa::OpenApi;
+use super::{
+    get_badge_handler, get_compare_handler, get_contests_handler, get_graph_handler, get_history_handler, get_rate_handler,
+    get_ratings_handler, get_stats_handler, get_users_handler,
+};
+
+/// The OpenAPI document of every route in `router::api_routes`.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Algo Stats API", description = "API providing statistics for traP team-algorithm."),
+    servers(
+        (url = "https://algo-stats.trap.show", description = "Production server"),
+        (url = "http://localhost:3000", description = "Development server"),
+    ),
+    tags(
+        (name = "Users", description = "Operations related to users"),
+        (name = "Ratings", description = "Operations related to ratings"),
+        (name = "Contests", description = "Operations related to AtCoder contests"),
+        (name = "Stats", description = "Statistics of the club"),
+    ),
+    paths(
+        get_users_handler::handler,
+        get_history_handler::handler,
+        get_ratings_handler::handler,
+        get_graph_handler::handler,
+        get_compare_handler::handler,
+        get_compare_handler::svg_handler,
+        get_rate_handler::heur_handler,
+        get_rate_handler::algo_handler,
+        get_badge_handler::handler,
+        get_contests_handler::upcoming_handler,
+        get_contests_handler::results_handler,
+        get_stats_handler::handler,
+    ),
+)]
+pub struct ApiDoc;
+
+pub async fn handler() -> impl IntoResponse {
+    // Swagger UI runs on another origin, see swagger.Dockerfile
+    ([("Access-Control-Allow-Origin", "*")], Json(ApiDoc::openapi()))
+}
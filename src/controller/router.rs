@@ -0,0 +1,94 @@
+use axum::{Router, routing::{MethodRouter, get}};
+use super::{
+    get_badge_handler, get_compare_handler, get_contests_handler, get_graph_handler, get_history_handler, get_rate_handler,
+    get_ratings_handler, get_stats_handler, get_users_handler,
+};
+
+/// The routes documented in `openapi::ApiDoc`. Their paths must match the document exactly.
+pub fn api_routes<PR>() -> Vec<(&'static str, MethodRouter)>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    vec![
+        ("/users", get(get_users_handler::handler::<PR>)),
+        ("/users/{trapAccountName}/history", get(get_history_handler::handler::<PR>)),
+        ("/users/{trapAccountName}/ratings", get(get_ratings_handler::handler::<PR>)),
+        ("/users/{trapAccountName}/graph.svg", get(get_graph_handler::handler::<PR>)),
+        ("/compare", get(get_compare_handler::handler::<PR>)),
+        ("/compare.svg", get(get_compare_handler::svg_handler::<PR>)),
+        ("/rate/heuristic/{trapAccountName}", get(get_rate_handler::heur_handler::<PR>)),
+        ("/rate/algorithm/{trapAccountName}", get(get_rate_handler::algo_handler::<PR>)),
+        ("/contests/upcoming", get(get_contests_handler::upcoming_handler::<PR>)),
+        ("/contests/{screenName}/results", get(get_contests_handler::results_handler::<PR>)),
+        ("/stats", get(get_stats_handler::handler::<PR>)),
+        ("/badge/{contestType}/{file}", get(get_badge_handler::handler::<PR>)),
+    ]
+}
+
+/// The public API and its OpenAPI document at `/openapi.json`. `PR` is expected as an extension.
+pub fn api_router<PR>() -> Router
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    api_routes::<PR>()
+        .into_iter()
+        .fold(Router::new(), |router, (path, method_router)| router.route(path, method_router))
+        .route("/openapi.json", get(super::openapi::handler))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::BTreeSet, sync::Arc};
+    use axum::Extension;
+    use utoipa::OpenApi as _;
+    use crate::controller::openapi::ApiDoc;
+    use crate::testing::{fake_server::FakeServer, in_memory_persist_repository::InMemoryPersistRepository};
+
+    #[tokio::test]
+    async fn test_routes_match_openapi() {
+        let spec = ApiDoc::openapi();
+        let documented = spec.paths.paths
+            .keys()
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>();
+        let routed = api_routes::<InMemoryPersistRepository>()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect::<BTreeSet<_>>();
+        assert_eq!(routed, documented);
+
+        let router = api_router::<InMemoryPersistRepository>()
+            .layer(Extension(Arc::new(InMemoryPersistRepository::default())));
+        let server = FakeServer::start(router).await;
+        let client = reqwest::Client::new();
+        let served = client
+            .get(format!("{}/openapi.json", server.base_url))
+            .send()
+            .await
+            .unwrap()
+            .json::<serde_json::Value>()
+            .await
+            .unwrap();
+        assert_eq!(served, serde_json::to_value(&spec).unwrap());
+
+        let param = regex::Regex::new(r"\{[^}]+\}").unwrap();
+        for (path, item) in spec.paths.paths.iter() {
+            let methods = [
+                (reqwest::Method::GET, item.get.is_some()),
+                (reqwest::Method::POST, item.post.is_some()),
+                (reqwest::Method::PUT, item.put.is_some()),
+                (reqwest::Method::DELETE, item.delete.is_some()),
+            ];
+            let url = format!("{}{}", server.base_url, param.replace_all(path, "alice"));
+            for (method, documented) in methods {
+                let response = client.request(method.clone(), &url).send().await.unwrap();
+                let status = response.status();
+                // Unrouted requests get an empty 404 or a 405 from the router itself
+                let routed = status != reqwest::StatusCode::METHOD_NOT_ALLOWED
+                    && !(status == reqwest::StatusCode::NOT_FOUND && response.bytes().await.unwrap().is_empty());
+                assert_eq!(routed, documented, "{} {} answered {}", method, path, status);
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use axum::{
+    http::{HeaderMap, header},
+    response::{IntoResponse, Response},
+};
+use reqwest::StatusCode;
+use std::hash::{Hash, Hasher};
+
+/// Responds with `svg`, or with 304 if the request's `If-None-Match` already has it.
+///
+/// Images change at most once per update run, so `max_age_secs` can be generous.
+pub fn cached_svg(svg: String, request_headers: &HeaderMap, max_age_secs: u64) -> Response {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    svg.hash(&mut hasher);
+    let etag = format!("\"{:016x}\"", hasher.finish());
+    let cache_headers = [
+        (header::CACHE_CONTROL, format!("public, max-age={}", max_age_secs)),
+        (header::ETAG, etag.clone()),
+    ];
+    let is_fresh = request_headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+    if is_fresh {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+    (
+        StatusCode::OK,
+        cache_headers,
+        [(header::CONTENT_TYPE, "image/svg+xml; charset=utf-8")],
+        svg,
+    ).into_response()
+}
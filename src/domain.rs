@@ -3,4 +3,9 @@ pub mod ac_account_updater;
 pub mod detail_updater;
 pub mod traq_repository;
 pub mod dto;
-pub mod persist_repository;
\ No newline at end of file
+pub mod persist_repository;
+pub mod contest_results_fetcher;
+pub mod traq_bot;
+pub mod rating_color;
+pub mod notifier;
+pub mod contest_schedule_fetcher;
\ No newline at end of file
@@ -4,5 +4,6 @@ use async_trait::async_trait;
 
 #[async_trait]
 pub trait TrapMemberAcAccountUpdater: Send + Sync + 'static{
-    async fn get(&self) -> Result<Vec<crate::domain::entity::TrapMemberWithAcAccount>>;
+    /// Fetches the AtCoder link of each traP member. Failures are reported per traP account name.
+    async fn get(&self) -> Result<crate::domain::entity::PartialFetch<Vec<crate::domain::entity::TrapMemberWithAcAccount>>>;
 }
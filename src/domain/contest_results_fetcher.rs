@@ -0,0 +1,16 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use super::entity::*;
+use std::collections::HashMap;
+
+#[async_trait]
+pub trait ContestResultsFetcher: Send + Sync + 'static {
+    /// Returns the rated contests that ended in `(since, until]`, oldest first.
+    async fn get_finished_contests(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<FinishedContest>>;
+    /// Returns every participant's result in a contest, keyed by AtCoder username.
+    async fn get_results(&self, contest: &FinishedContest) -> Result<HashMap<String, ContestResult>>;
+}
@@ -0,0 +1,9 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use super::entity::*;
+
+#[async_trait]
+pub trait ContestScheduleFetcher: Send + Sync + 'static {
+    /// Returns the upcoming ABC, ARC, AGC and AHC, soonest first.
+    async fn get_upcoming_contests(&self) -> Result<Vec<Contest>>;
+}
@@ -5,5 +5,6 @@ use std::collections::HashMap;
 
 #[async_trait]
 pub trait DetailedInfoUpdater: Send + Sync + 'static {
-    async fn get(&self, usernames: Vec<String>) -> Result<HashMap<String, AcDetailedInfo>>;
+    /// Fetches the contest history of each AtCoder user. Failures are reported per username.
+    async fn get(&self, usernames: Vec<String>) -> Result<PartialFetch<HashMap<String, AcDetailedInfo>>>;
 }
@@ -1,20 +1,344 @@
 use serde::Serialize;
 use sqlx::FromRow;
+use super::rating_color::{RatingColor, RatingTier};
 
-#[derive(Debug, Clone, Serialize, FromRow)]
+#[derive(Debug, Clone, Serialize, FromRow, utoipa::ToSchema)]
 pub struct User {
+    /// The trap account name of the user.
     #[serde(rename = "trapAccountName")]
+    #[schema(example = "comavius")]
     pub trap_account_name: String,
+    /// The AtCoder account name linked on traPortfolio.
     #[serde(rename = "atcoderAccountName")]
+    #[schema(example = "comavius")]
     pub atcoder_account_name: Option<String>,
+    /// The algorithm rating, 0 if unrated.
     #[serde(rename = "atcoderRating")]
+    #[schema(example = 1866)]
     pub atcoder_rating: Option<i32>,
+    /// The heuristic rating, 0 if unrated.
     #[serde(rename = "heuristicRating")]
+    #[schema(example = 1854)]
     pub heuristic_rating: Option<i32>,
     #[serde(rename = "isAlgoTeam")]
     pub is_algo_team: Option<bool>,
     #[serde(rename = "isActive")]
     pub is_active: Option<bool>,
     #[serde(rename = "grade")]
+    #[schema(example = "23B")]
     pub grade: Option<String>,
+    /// Why the last update could not fetch this user, or null if it succeeded.
+    #[serde(rename = "lastFetchError")]
+    pub last_fetch_error: Option<String>,
+}
+
+/// A user with the colors of their ratings.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct UserResponse {
+    #[serde(flatten)]
+    pub user: User,
+    /// The color of the algorithm rating, or null if unrated.
+    #[serde(rename = "atcoderTier")]
+    pub atcoder_tier: Option<RatingTier>,
+    /// The color of the heuristic rating, or null if unrated.
+    #[serde(rename = "heuristicTier")]
+    pub heuristic_tier: Option<RatingTier>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, utoipa::ToSchema)]
+pub struct RatingSnapshot {
+    #[serde(rename = "trapAccountName")]
+    pub trap_account_name: String,
+    #[serde(rename = "takenAt")]
+    pub taken_at: chrono::DateTime<chrono::Utc>,
+    #[serde(rename = "atcoderRating")]
+    pub atcoder_rating: Option<i32>,
+    #[serde(rename = "heuristicRating")]
+    pub heuristic_rating: Option<i32>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct UpdateRun {
+    pub id: i64,
+    pub mode: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, utoipa::ToSchema)]
+pub struct ScheduledContest {
+    /// The screen name of the contest in its URL.
+    #[serde(rename = "contestId")]
+    #[schema(example = "abc401")]
+    pub contest_id: String,
+    #[serde(rename = "contestName")]
+    pub contest_name: String,
+    #[serde(rename = "contestUrl")]
+    pub contest_url: String,
+    #[serde(rename = "startTime")]
+    pub start_time: chrono::DateTime<chrono::Utc>,
+    #[serde(rename = "endTime")]
+    pub end_time: chrono::DateTime<chrono::Utc>,
+    #[serde(rename = "durationMinutes")]
+    pub duration_minutes: i32,
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct ContestParticipant {
+    #[serde(rename = "trapAccountName")]
+    pub trap_account_name: String,
+    #[serde(rename = "atcoderAccountName")]
+    pub atcoder_account_name: String,
+    #[serde(rename = "isRated")]
+    pub is_rated: bool,
+    #[serde(rename = "place")]
+    pub place: i32,
+    #[serde(rename = "performance")]
+    pub performance: i32,
+    #[serde(rename = "oldRating")]
+    pub old_rating: i32,
+    #[serde(rename = "newRating")]
+    pub new_rating: i32,
+    #[serde(rename = "ratingDelta")]
+    pub rating_delta: i32,
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct ContestReport {
+    #[serde(rename = "contestScreenName")]
+    pub contest_screen_name: String,
+    #[serde(rename = "contestName")]
+    pub contest_name: String,
+    /// `algorithm` or `heuristic`.
+    #[serde(rename = "contestType")]
+    pub contest_type: String,
+    #[serde(rename = "endTime")]
+    pub end_time: chrono::DateTime<chrono::Utc>,
+    /// Ordered by place.
+    #[serde(rename = "participants")]
+    pub participants: Vec<ContestParticipant>,
+    #[serde(rename = "bestPerformer")]
+    pub best_performer: Option<ContestParticipant>,
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct ContestHistoryEntry {
+    #[serde(rename = "contestScreenName")]
+    pub contest_screen_name: String,
+    #[serde(rename = "contestName")]
+    pub contest_name: String,
+    #[serde(rename = "endTime")]
+    pub end_time: chrono::DateTime<chrono::Utc>,
+    #[serde(rename = "isRated")]
+    pub is_rated: bool,
+    #[serde(rename = "place")]
+    pub place: i32,
+    #[serde(rename = "performance")]
+    pub performance: i32,
+    #[serde(rename = "oldRating")]
+    pub old_rating: i32,
+    #[serde(rename = "newRating")]
+    pub new_rating: i32,
+}
+
+impl From<super::entity::ContestResult> for ContestHistoryEntry {
+    fn from(result: super::entity::ContestResult) -> Self {
+        ContestHistoryEntry {
+            contest_screen_name: result.contest_screen_name,
+            contest_name: result.contest_name,
+            end_time: result.end_time,
+            is_rated: result.is_rated,
+            place: result.place,
+            performance: result.performance,
+            old_rating: result.old_rating,
+            new_rating: result.new_rating,
+        }
+    }
+}
+
+impl User {
+    pub fn rating(&self, contest_type: super::entity::ContestType) -> Option<i32> {
+        match contest_type {
+            super::entity::ContestType::Algorithm => self.atcoder_rating,
+            super::entity::ContestType::Heuristic => self.heuristic_rating,
+        }
+    }
+}
+
+impl UserResponse {
+    /// `rated_contests` counts the rated contests of each type.
+    pub fn new(user: User, rated_contests: impl Fn(super::entity::ContestType) -> i64) -> Self {
+        let tier = |rating: Option<i32>, contest_type| {
+            rating.and_then(|rating| RatingTier::new(rating, rated_contests(contest_type)))
+        };
+        UserResponse {
+            atcoder_tier: tier(user.atcoder_rating, super::entity::ContestType::Algorithm),
+            heuristic_tier: tier(user.heuristic_rating, super::entity::ContestType::Heuristic),
+            user,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct ColorCount {
+    #[serde(rename = "color")]
+    pub color: RatingColor,
+    #[serde(rename = "count")]
+    pub count: i64,
+}
+
+/// Rating aggregates over a group of active members. Mean, median and max only count rated members.
+#[derive(Debug, Clone, Default, Serialize, utoipa::ToSchema)]
+pub struct RatingStats {
+    #[serde(rename = "members")]
+    pub members: i64,
+    /// Members with a linked AtCoder account.
+    #[serde(rename = "linked")]
+    pub linked: i64,
+    /// Members with a positive rating.
+    #[serde(rename = "rated")]
+    pub rated: i64,
+    #[serde(rename = "meanRating")]
+    pub mean_rating: Option<f64>,
+    #[serde(rename = "medianRating")]
+    pub median_rating: Option<f64>,
+    #[serde(rename = "maxRating")]
+    pub max_rating: Option<i32>,
+}
+
+impl From<super::entity::RatingAggregate> for RatingStats {
+    fn from(aggregate: super::entity::RatingAggregate) -> Self {
+        RatingStats {
+            members: aggregate.members,
+            linked: aggregate.linked,
+            rated: aggregate.rated,
+            mean_rating: aggregate.mean_rating,
+            median_rating: aggregate.median_rating,
+            max_rating: aggregate.max_rating,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct GradeStats {
+    /// The grade, or null for members without one.
+    #[serde(rename = "grade")]
+    #[schema(example = "23B")]
+    pub grade: Option<String>,
+    #[serde(flatten)]
+    pub stats: RatingStats,
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct ClubStats {
+    #[serde(rename = "contestType")]
+    pub contest_type: String,
+    #[serde(rename = "members")]
+    pub members: i64,
+    #[serde(rename = "linked")]
+    pub linked: i64,
+    /// The share of members with a linked AtCoder account, from 0 to 1.
+    #[serde(rename = "linkedShare")]
+    pub linked_share: f64,
+    /// Rated members per color, lowest color first.
+    #[serde(rename = "colors")]
+    pub colors: Vec<ColorCount>,
+    #[serde(rename = "grades")]
+    pub grades: Vec<GradeStats>,
+    #[serde(rename = "algoTeam")]
+    pub algo_team: RatingStats,
+    #[serde(rename = "others")]
+    pub others: RatingStats,
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct ComparedUser {
+    #[serde(rename = "trapAccountName")]
+    pub trap_account_name: String,
+    #[serde(rename = "atcoderAccountName")]
+    pub atcoder_account_name: String,
+    #[serde(rename = "rating")]
+    pub rating: Option<i32>,
+    /// The rated contests, oldest first.
+    #[serde(rename = "timeline")]
+    pub timeline: Vec<ContestHistoryEntry>,
+    /// Shared contests in which this user placed best among the compared users.
+    #[serde(rename = "wins")]
+    pub wins: i64,
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct HeadToHeadEntry {
+    #[serde(rename = "trapAccountName")]
+    pub trap_account_name: String,
+    #[serde(rename = "place")]
+    pub place: i32,
+    #[serde(rename = "performance")]
+    pub performance: i32,
+    #[serde(rename = "ratingDelta")]
+    pub rating_delta: i32,
+}
+
+/// A contest entered by at least two of the compared users.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct HeadToHeadContest {
+    #[serde(rename = "contestScreenName")]
+    pub contest_screen_name: String,
+    #[serde(rename = "contestName")]
+    pub contest_name: String,
+    #[serde(rename = "endTime")]
+    pub end_time: chrono::DateTime<chrono::Utc>,
+    /// Ordered by place.
+    #[serde(rename = "entries")]
+    pub entries: Vec<HeadToHeadEntry>,
+    /// The best placed user, or null on a tie.
+    #[serde(rename = "winner")]
+    pub winner: Option<String>,
+}
+
+/// How two of the compared users did against each other in their shared contests.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct HeadToHeadRecord {
+    #[serde(rename = "user")]
+    pub user: String,
+    #[serde(rename = "opponent")]
+    pub opponent: String,
+    #[serde(rename = "wins")]
+    pub wins: i64,
+    #[serde(rename = "losses")]
+    pub losses: i64,
+    #[serde(rename = "draws")]
+    pub draws: i64,
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct Comparison {
+    #[serde(rename = "contestType")]
+    pub contest_type: String,
+    /// In the order they were asked for.
+    #[serde(rename = "users")]
+    pub users: Vec<ComparedUser>,
+    /// Newest first.
+    #[serde(rename = "headToHead")]
+    pub head_to_head: Vec<HeadToHeadContest>,
+    /// One record per pair of users.
+    #[serde(rename = "records")]
+    pub records: Vec<HeadToHeadRecord>,
+}
+
+/// The state reported by `/readyz`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Readiness {
+    #[serde(rename = "ready")]
+    pub ready: bool,
+    #[serde(rename = "databaseReachable")]
+    pub database_reachable: bool,
+    /// When the last successful update finished, or null if none has or the database is down.
+    #[serde(rename = "lastUpdateAt")]
+    pub last_update_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// True unless an update is known to have finished within `maxDataAgeSeconds`.
+    #[serde(rename = "isStale")]
+    pub is_stale: bool,
+    #[serde(rename = "maxDataAgeSeconds")]
+    pub max_data_age_seconds: i64,
 }
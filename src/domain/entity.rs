@@ -18,6 +18,84 @@ pub struct AcDetailedInfo {
     pub heur_rating: Vec<ContestResult>,
 }
 
+/// A single item that could not be fetched, e.g. a renamed AtCoder account.
+#[derive(Debug, Clone)]
+pub struct FetchFailure {
+    pub key: String,
+    pub reason: String,
+}
+
+/// Result of a batch fetch in which individual items may fail without aborting the batch.
+#[derive(Debug, Clone)]
+pub struct PartialFetch<T> {
+    pub fetched: T,
+    pub failures: Vec<FetchFailure>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContestType {
+    Algorithm,
+    Heuristic,
+}
+
+impl ContestType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContestType::Algorithm => "algorithm",
+            ContestType::Heuristic => "heuristic",
+        }
+    }
+}
+
+impl std::str::FromStr for ContestType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "algorithm" => Ok(ContestType::Algorithm),
+            "heuristic" => Ok(ContestType::Heuristic),
+            other => Err(anyhow::anyhow!("Unknown contest type: {}", other)),
+        }
+    }
+}
+
+/// How `Updater::update` finds new rating changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateMode {
+    /// Download every linked account's full history.
+    User,
+    /// Download the results of contests that finished since the last run.
+    Contest,
+}
+
+impl UpdateMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateMode::User => "user",
+            UpdateMode::Contest => "contest",
+        }
+    }
+}
+
+impl std::str::FromStr for UpdateMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(UpdateMode::User),
+            "contest" => Ok(UpdateMode::Contest),
+            other => Err(anyhow::anyhow!("Unknown update mode: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FinishedContest {
+    pub contest_id: String,
+    pub contest_type: ContestType,
+    pub end_time: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ContestResult {
     pub is_rated: bool,
@@ -28,12 +106,115 @@ pub struct ContestResult {
     pub performance: i32,
     pub contest_screen_name: String,
     pub contest_name: String,
-    pub end_time: String,
+    pub end_time: chrono::DateTime<chrono::Utc>,
 }
 
+/// A contest on the AtCoder schedule.
 #[derive(Debug, Clone)]
 pub struct Contest {
+    /// The screen name in the contest URL, e.g. `abc401`.
+    pub contest_id: String,
     pub contest_name: String,
     pub contest_url: String,
-    pub set_at: String,
+    pub start_time: chrono::DateTime<chrono::Utc>,
+    pub end_time: chrono::DateTime<chrono::Utc>,
+}
+
+/// A contest result of a traP member.
+#[derive(Debug, Clone)]
+pub struct ContestParticipation {
+    pub trap_account_name: String,
+    pub atcoder_account_name: String,
+    pub contest_type: ContestType,
+    pub result: ContestResult,
+}
+
+/// A member whose rating moved into a higher color in an update run.
+#[derive(Debug, Clone)]
+pub struct ColorPromotion {
+    pub trap_account_name: String,
+    pub atcoder_account_name: String,
+    pub contest_type: ContestType,
+    pub old_rating: i32,
+    pub new_rating: i32,
+    pub old_color: super::rating_color::RatingColor,
+    pub new_color: super::rating_color::RatingColor,
+    /// The latest contest of the member, which is what moved the rating.
+    pub contest_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserSortKey {
+    TrapAccountName,
+    AtcoderRating,
+    HeuristicRating,
+    Grade,
+}
+
+/// An order of users such as `atcoderRating:desc`. Users without a value always come last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserSort {
+    pub key: UserSortKey,
+    pub descending: bool,
+}
+
+impl std::str::FromStr for UserSort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, direction) = s.split_once(':').unwrap_or((s, "asc"));
+        let key = match key {
+            "trapAccountName" => UserSortKey::TrapAccountName,
+            "atcoderRating" => UserSortKey::AtcoderRating,
+            "heuristicRating" => UserSortKey::HeuristicRating,
+            "grade" => UserSortKey::Grade,
+            other => return Err(anyhow::anyhow!("Unknown sort key: {}", other)),
+        };
+        let descending = match direction {
+            "asc" => false,
+            "desc" => true,
+            other => return Err(anyhow::anyhow!("Unknown sort direction: {}", other)),
+        };
+        Ok(UserSort { key, descending })
+    }
+}
+
+/// Filters, order and page of a user listing. `None` filters match everyone.
+#[derive(Debug, Clone, Default)]
+pub struct UserQuery {
+    pub grade: Option<String>,
+    pub is_algo_team: Option<bool>,
+    pub is_active: Option<bool>,
+    pub has_atcoder: Option<bool>,
+    pub min_atcoder_rating: Option<i32>,
+    pub min_heuristic_rating: Option<i32>,
+    /// Ties and the default order are by traP account name.
+    pub sort: Option<UserSort>,
+    pub limit: Option<u32>,
+    pub offset: u32,
+}
+
+/// How active users are grouped for rating aggregates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserGrouping {
+    /// A single group of everyone, with `None` as its key.
+    All,
+    /// By grade, with `None` for users without one.
+    Grade,
+    /// Into `algoTeam` and `others`.
+    AlgoTeam,
+}
+
+/// Rating aggregates over a group of active users. Mean, median and max only count rated users.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatingAggregate {
+    pub group: Option<String>,
+    pub members: i64,
+    /// Members with a linked AtCoder account.
+    pub linked: i64,
+    /// Members with a positive rating.
+    pub rated: i64,
+    pub mean_rating: Option<f64>,
+    pub median_rating: Option<f64>,
+    pub max_rating: Option<i32>,
 }
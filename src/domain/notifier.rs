@@ -0,0 +1,18 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use super::dto::{ContestReport, ScheduledContest};
+use super::entity::*;
+
+#[async_trait]
+pub trait Notifier: Send + Sync + 'static {
+    /// Congratulates members who reached a new rating color.
+    async fn notify_color_promotions(&self, promotions: &[ColorPromotion]) -> Result<()>;
+    /// Reminds members of a contest starting in `starts_in`.
+    async fn notify_contest_reminder(
+        &self,
+        contest: &ScheduledContest,
+        starts_in: chrono::Duration,
+    ) -> Result<()>;
+    /// Shares how members did in a finished contest.
+    async fn notify_contest_report(&self, report: &ContestReport) -> Result<()>;
+}
@@ -1,10 +1,106 @@
 use async_trait::async_trait;
 use anyhow::Result;
 use super::dto::*;
+use super::entity::{
+    Contest, ContestParticipation, ContestResult, ContestType, FinishedContest, RatingAggregate, UpdateMode, UserGrouping,
+    UserQuery,
+};
 
 #[async_trait]
 pub trait PersistRepository: Send + Sync + 'static {
     async fn get_users(&self) -> Result<Vec<User>>;
+    /// Returns one page of the users matching `query` and how many match in total.
+    async fn find_users(&self, query: &UserQuery) -> Result<(Vec<User>, i64)>;
     async fn set_users(&self, users: Vec<User>) -> Result<()>;
+    /// Counts active users with a positive rating per band of `band_width` points,
+    /// keyed by the lowest rating of the band.
+    async fn count_rated_users_by_band(&self, contest_type: ContestType, band_width: i32) -> Result<Vec<(i32, i64)>>;
+    /// Aggregates the ratings of active users per group, ordered by group key.
+    async fn get_rating_aggregates(
+        &self,
+        contest_type: ContestType,
+        grouping: UserGrouping,
+    ) -> Result<Vec<RatingAggregate>>;
     async fn get_user(&self, trap_account_name: &str) -> Result<Option<User>>;
+    /// Upserts the contest history of an AtCoder account, keyed by contest screen name.
+    async fn set_contest_results(
+        &self,
+        atcoder_account_name: &str,
+        contest_type: ContestType,
+        results: Vec<ContestResult>,
+    ) -> Result<()>;
+    /// Returns the stored contest history of an AtCoder account, oldest first.
+    async fn get_contest_results(
+        &self,
+        atcoder_account_name: &str,
+        contest_type: ContestType,
+    ) -> Result<Vec<ContestResult>>;
+    /// Counts the rated contests of the given AtCoder accounts, keyed by account name and contest type.
+    async fn get_rated_contest_counts(
+        &self,
+        atcoder_account_names: &[String],
+    ) -> Result<std::collections::HashMap<(String, ContestType), i64>>;
+    /// Returns the results of traP members in a contest, joined on their current AtCoder account.
+    async fn get_contest_participations(
+        &self,
+        contest_screen_name: &str,
+    ) -> Result<Vec<ContestParticipation>>;
+    /// Appends one snapshot per user, all stamped with the time of the update run.
+    async fn add_rating_snapshots(
+        &self,
+        taken_at: chrono::DateTime<chrono::Utc>,
+        users: &[User],
+    ) -> Result<()>;
+    /// Returns the snapshots of a user taken within `[from, to]`, oldest first.
+    async fn get_rating_snapshots(
+        &self,
+        trap_account_name: &str,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<RatingSnapshot>>;
+    /// Returns each user's latest snapshot taken strictly before `before`.
+    async fn get_latest_rating_snapshots_before(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<RatingSnapshot>>;
+    /// Records a successful `Updater::update` run.
+    async fn add_update_run(
+        &self,
+        mode: UpdateMode,
+        started_at: chrono::DateTime<chrono::Utc>,
+        finished_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()>;
+    async fn get_last_update_run(&self) -> Result<Option<UpdateRun>>;
+    /// Records finished contests as pending until their results are applied.
+    /// Contests recorded before are left as they are.
+    async fn add_pending_contests(&self, contests: &[FinishedContest]) -> Result<()>;
+    /// Returns the pending contests that ended after `ended_after`, oldest first.
+    async fn get_pending_contests(
+        &self,
+        ended_after: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<FinishedContest>>;
+    async fn mark_contest_applied(
+        &self,
+        contest_id: &str,
+        applied_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()>;
+    /// Marks the digest of an update run as posted.
+    /// Returns false if it was already marked, so that each run is posted at most once.
+    async fn claim_digest(&self, run_id: i64) -> Result<bool>;
+    /// Undoes `claim_digest` after the digest could not be posted.
+    async fn release_digest(&self, run_id: i64) -> Result<()>;
+    /// Upserts scheduled contests, keyed by contest id.
+    async fn set_contests(&self, contests: Vec<Contest>) -> Result<()>;
+    /// Returns the contests starting after `after`, soonest first.
+    async fn get_contests_starting_after(
+        &self,
+        after: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<ScheduledContest>>;
+    /// Marks the reminder of a contest at one offset as posted.
+    /// Returns false if it was already marked.
+    async fn claim_contest_reminder(&self, contest_id: &str, offset_minutes: i64) -> Result<bool>;
+    /// Undoes `claim_contest_reminder` after the reminder could not be posted.
+    async fn release_contest_reminder(&self, contest_id: &str, offset_minutes: i64) -> Result<()>;
+    /// Checks that the database can be reached.
+    async fn ping(&self) -> Result<()>;
 }
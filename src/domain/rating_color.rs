@@ -0,0 +1,156 @@
+/// The colors AtCoder shows next to a rating, lowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RatingColor {
+    Gray,
+    Brown,
+    Green,
+    Cyan,
+    Blue,
+    Yellow,
+    Orange,
+    Red,
+}
+
+/// Every color spans this many rating points, except red which has no upper bound.
+pub const COLOR_WIDTH: i32 = 400;
+// AtCoder marks ratings as provisional until this many rated contests
+const PROVISIONAL_RATED_CONTESTS: i64 = 10;
+
+impl RatingColor {
+    pub const ALL: [RatingColor; 8] = [
+        RatingColor::Gray,
+        RatingColor::Brown,
+        RatingColor::Green,
+        RatingColor::Cyan,
+        RatingColor::Blue,
+        RatingColor::Yellow,
+        RatingColor::Orange,
+        RatingColor::Red,
+    ];
+
+    pub fn from_rating(rating: i32) -> Self {
+        match rating.max(0) / COLOR_WIDTH {
+            0 => RatingColor::Gray,
+            1 => RatingColor::Brown,
+            2 => RatingColor::Green,
+            3 => RatingColor::Cyan,
+            4 => RatingColor::Blue,
+            5 => RatingColor::Yellow,
+            6 => RatingColor::Orange,
+            _ => RatingColor::Red,
+        }
+    }
+
+    /// The lowest rating of this color.
+    pub fn lower_bound(&self) -> i32 {
+        *self as i32 * COLOR_WIDTH
+    }
+
+    /// The lowest rating of the next color, or `None` for red.
+    pub fn upper_bound(&self) -> Option<i32> {
+        match self {
+            RatingColor::Red => None,
+            _ => Some(self.lower_bound() + COLOR_WIDTH),
+        }
+    }
+
+    /// The color as AtCoder draws it, e.g. `#008000` for green.
+    pub fn hex(&self) -> &'static str {
+        match self {
+            RatingColor::Gray => "#808080",
+            RatingColor::Brown => "#804000",
+            RatingColor::Green => "#008000",
+            RatingColor::Cyan => "#00C0C0",
+            RatingColor::Blue => "#0000FF",
+            RatingColor::Yellow => "#C0C000",
+            RatingColor::Orange => "#FF8000",
+            RatingColor::Red => "#FF0000",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RatingColor::Gray => "gray",
+            RatingColor::Brown => "brown",
+            RatingColor::Green => "green",
+            RatingColor::Cyan => "cyan",
+            RatingColor::Blue => "blue",
+            RatingColor::Yellow => "yellow",
+            RatingColor::Orange => "orange",
+            RatingColor::Red => "red",
+        }
+    }
+}
+
+/// Where a rating sits among the colors.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+pub struct RatingTier {
+    pub color: RatingColor,
+    /// The lowest rating of the color.
+    #[serde(rename = "lowerBound")]
+    #[schema(example = 1600)]
+    pub lower_bound: i32,
+    /// The lowest rating of the next color, or null for red.
+    #[serde(rename = "upperBound")]
+    #[schema(example = 2000)]
+    pub upper_bound: Option<i32>,
+    /// How many points are missing for the next color, or null for red.
+    #[serde(rename = "pointsToNextColor")]
+    #[schema(example = 134)]
+    pub points_to_next_color: Option<i32>,
+    /// Whether AtCoder shows the rating as provisional because of few rated contests.
+    /// Such ratings are lowered by AtCoder and tend to rise quickly.
+    #[serde(rename = "isProvisional")]
+    pub is_provisional: bool,
+}
+
+impl RatingTier {
+    /// Returns `None` for unrated accounts, i.e. a rating of 0 or no rated contests.
+    pub fn new(rating: i32, rated_contests: i64) -> Option<Self> {
+        if rating <= 0 || rated_contests <= 0 {
+            return None;
+        }
+        let color = RatingColor::from_rating(rating);
+        let upper_bound = color.upper_bound();
+        Some(RatingTier {
+            color,
+            lower_bound: color.lower_bound(),
+            upper_bound,
+            points_to_next_color: upper_bound.map(|bound| bound - rating),
+            is_provisional: rated_contests < PROVISIONAL_RATED_CONTESTS,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_rating() {
+        assert_eq!(RatingColor::from_rating(0), RatingColor::Gray);
+        assert_eq!(RatingColor::from_rating(399), RatingColor::Gray);
+        assert_eq!(RatingColor::from_rating(400), RatingColor::Brown);
+        assert_eq!(RatingColor::from_rating(1623), RatingColor::Blue);
+        assert_eq!(RatingColor::from_rating(2799), RatingColor::Orange);
+        assert_eq!(RatingColor::from_rating(4000), RatingColor::Red);
+        assert!(RatingColor::Cyan > RatingColor::Green);
+    }
+    #[test]
+    fn test_rating_tier() {
+        let tier = RatingTier::new(1866, 25).unwrap();
+        assert_eq!(tier.color, RatingColor::Blue);
+        assert_eq!((tier.lower_bound, tier.upper_bound), (1600, Some(2000)));
+        assert_eq!(tier.points_to_next_color, Some(134));
+        assert!(!tier.is_provisional);
+
+        let tier = RatingTier::new(3012, 9).unwrap();
+        assert_eq!(tier.color, RatingColor::Red);
+        assert_eq!((tier.upper_bound, tier.points_to_next_color), (None, None));
+        assert!(tier.is_provisional);
+
+        assert_eq!(RatingTier::new(0, 0), None);
+        assert_eq!(RatingTier::new(0, 3), None);
+    }
+}
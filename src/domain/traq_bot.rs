@@ -0,0 +1,8 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait TraqBot: Send + Sync + 'static {
+    /// Posts `content` as Markdown to the channel, expanding mentions and channel links.
+    async fn post_message(&self, channel_id: &str, content: &str) -> Result<()>;
+}
@@ -1,4 +1,12 @@
 pub mod detail_updater;
 pub mod ac_account_updater;
 pub mod traq_repository;
-pub mod persist_repository;
\ No newline at end of file
+pub mod persist_repository;
+pub mod migrator;
+pub mod contest_results_fetcher;
+pub mod client_config;
+pub mod http_client;
+pub mod traq_bot;
+pub mod traq_notifier;
+pub mod contest_schedule_fetcher;
+pub mod metrics;
\ No newline at end of file
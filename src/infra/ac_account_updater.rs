@@ -4,7 +4,12 @@ use std::vec;
 use anyhow::Result;
 use async_trait::async_trait;
 use uuid::Uuid;
+use crate::domain::entity::{FetchFailure, PartialFetch, TrapMemberWithAcAccount};
 
+use super::client_config::ClientConfig;
+use super::http_client::HttpClient;
+
+static TRAPORTFOLIO_BASE_URL: &str = "https://portfolio.trap.jp/api/v1";
 static TRAPORTFOLIO_WAIT_TIME_MS: u64 = 200;
 static TRAPORTFOLIO_AC_ACCOUNT_TYPE_ID: i32 = 8;
 
@@ -35,57 +40,78 @@ struct TrapMemberDto {
 }
 
 pub struct TrapMemberAcAccountUpdaterImpl {
-    http_client: reqwest::Client,
+    http_client: HttpClient,
+    config: ClientConfig,
 }
 
 impl TrapMemberAcAccountUpdaterImpl {
-    pub fn new() -> Self {
-        let http_client = reqwest::Client::builder()
-            .build()
-            .expect("Failed to create HTTP client");
-        TrapMemberAcAccountUpdaterImpl { http_client }
+    pub fn new(config: ClientConfig) -> Self {
+        let http_client = HttpClient::new(&config);
+        TrapMemberAcAccountUpdaterImpl { http_client, config }
+    }
+
+    pub fn default_config() -> ClientConfig {
+        ClientConfig::new("traPortfolio", TRAPORTFOLIO_BASE_URL, std::time::Duration::from_millis(TRAPORTFOLIO_WAIT_TIME_MS))
     }
 }
 
 #[async_trait]
 impl crate::domain::ac_account_updater::TrapMemberAcAccountUpdater for TrapMemberAcAccountUpdaterImpl {
-    async fn get(&self) -> Result<Vec<crate::domain::entity::TrapMemberWithAcAccount>> {
+    async fn get(&self) -> Result<PartialFetch<Vec<TrapMemberWithAcAccount>>> {
         tracing::info!("Starting to fetch from traportfolio");
         // Fetch all members list
-        let all_members_url = "https://portfolio.trap.jp/api/v1/users";
+        let all_members_url = self.config.url("/users");
         let response = self
             .http_client
-            .get(all_members_url)
-            .header("Accept-Encoding", "gzip")
-            .send()
+            .send(|client| client.get(&all_members_url).header("Accept-Encoding", "gzip"))
             .await?
+            .error_for_status()?
             .bytes()
             .await?;
         let mut gz = flate2::read::GzDecoder::new(&response[..]);
         let members: Vec<TrapMemberMinimalDto> = serde_json::from_reader(&mut gz)?;
         // Fetch detailed info for each member
         let mut results = vec![];
+        let mut failures = vec![];
         for member in members {
-            let url = format!("https://portfolio.trap.jp/api/v1/users/{}", member.id);
-            let response = self
-                .http_client
-                .get(&url)
-                .send()
-                .await?;
-            let text = response.text().await?;
-            let member : TrapMemberDto = serde_json::from_str(&text)?;
-            results.push(crate::domain::entity::TrapMemberWithAcAccount {
-                trap_account_name: member.name,
-                ac_account_name: member
-                    .accounts
-                    .iter()
-                    .find(|account| account.type_ == TRAPORTFOLIO_AC_ACCOUNT_TYPE_ID)
-                    .map(|account| account.displayName.clone()),
-            });
-            // Wait for a while to avoid overwhelming the server
-            tokio::time::sleep(std::time::Duration::from_millis(TRAPORTFOLIO_WAIT_TIME_MS)).await;
+            match self.get_member(&member).await {
+                Ok(member) => {
+                    results.push(member);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to fetch {} from traportfolio: {}", member.name, e);
+                    failures.push(FetchFailure {
+                        key: member.name,
+                        reason: e.to_string(),
+                    });
+                }
+            }
         }
-        Ok(results)
+        Ok(PartialFetch {
+            fetched: results,
+            failures,
+        })
+    }
+}
+
+impl TrapMemberAcAccountUpdaterImpl {
+    async fn get_member(&self, member: &TrapMemberMinimalDto) -> Result<TrapMemberWithAcAccount> {
+        let url = self.config.url(&format!("/users/{}", member.id));
+        let response = self
+            .http_client
+            .send(|client| client.get(&url))
+            .await?
+            .error_for_status()?;
+        let text = response.text().await?;
+        let member : TrapMemberDto = serde_json::from_str(&text)?;
+        Ok(TrapMemberWithAcAccount {
+            trap_account_name: member.name,
+            ac_account_name: member
+                .accounts
+                .iter()
+                .find(|account| account.type_ == TRAPORTFOLIO_AC_ACCOUNT_TYPE_ID)
+                .map(|account| account.displayName.clone()),
+        })
     }
 }
 
@@ -93,17 +119,17 @@ impl crate::domain::ac_account_updater::TrapMemberAcAccountUpdater for TrapMembe
 mod tests {
     use super::*;
     use crate::domain::ac_account_updater::TrapMemberAcAccountUpdater as _;
+    use crate::testing::fake_server::{FakeServer, traportfolio_router};
 
     #[tokio::test]
+    #[ignore = "hits the real traPortfolio API"]
     async fn test_get() {
-        let updater = TrapMemberAcAccountUpdaterImpl {
-            http_client: reqwest::Client::new(),
-        };
+        let updater = TrapMemberAcAccountUpdaterImpl::new(TrapMemberAcAccountUpdaterImpl::default_config());
         let result = updater.get().await;
         match result {
             Ok(data) => {
                 let mut has_ac_account = 0;
-                for member in data.iter() {
+                for member in data.fetched.iter() {
                     if let Some(ac_account_name) = &member.ac_account_name {
                         has_ac_account += 1;
                     }
@@ -111,8 +137,27 @@ mod tests {
                 println!("Members with AC account: {}", has_ac_account);
             }
             Err(e) => {
-                assert!(false, "Failed to fetch data{}", e);
+                panic!("Failed to fetch data{}", e);
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_get_from_fake_server() {
+        let server = FakeServer::start(traportfolio_router()).await;
+        let updater = TrapMemberAcAccountUpdaterImpl::new(server.config());
+        let result = updater.get().await.unwrap();
+        let ac_account_of = |name: &str| {
+            result.fetched
+                .iter()
+                .find(|member| member.trap_account_name == name)
+                .map(|member| member.ac_account_name.clone())
+        };
+        assert_eq!(result.fetched.len(), 4);
+        assert_eq!(ac_account_of("alice"), Some(Some("alice_ac".to_string())));
+        assert_eq!(ac_account_of("bob"), Some(Some("bob_ac".to_string())));
+        assert_eq!(ac_account_of("dave"), Some(None));
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].key, "eve");
+    }
 }
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+static DEFAULT_TIMEOUT_MS: u64 = 30_000;
+static DEFAULT_BURST: u32 = 1;
+static DEFAULT_MAX_RETRIES: u32 = 3;
+static DEFAULT_RETRY_BASE_DELAY_MS: u64 = 1_000;
+static DEFAULT_USER_AGENT: &str = concat!(
+    "algo-stats/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/traP-jp/algo-stats)"
+);
+
+/// Connection settings shared by every client of one upstream service.
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Name of the upstream service, labelling its metrics.
+    pub name: String,
+    /// Base URL without a trailing slash, e.g. `https://atcoder.jp`.
+    pub base_url: String,
+    pub timeout: Duration,
+    pub user_agent: String,
+    /// Average pause between consecutive requests to be gentle on the upstream.
+    pub wait_time: Duration,
+    /// How many requests may be sent back to back before `wait_time` applies.
+    pub burst: u32,
+    pub max_retries: u32,
+    /// First backoff delay, doubled on every retry.
+    pub retry_base_delay: Duration,
+}
+
+impl ClientConfig {
+    pub fn new(name: &str, base_url: &str, wait_time: Duration) -> Self {
+        ClientConfig {
+            name: name.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            timeout: Duration::from_millis(DEFAULT_TIMEOUT_MS),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            wait_time,
+            burst: DEFAULT_BURST,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: Duration::from_millis(DEFAULT_RETRY_BASE_DELAY_MS),
+        }
+    }
+
+    /// Overrides `default` with `{prefix}_BASE_URL`, `{prefix}_TIMEOUT_MS`, `{prefix}_USER_AGENT`,
+    /// `{prefix}_WAIT_TIME_MS`, `{prefix}_BURST`, `{prefix}_MAX_RETRIES` and
+    /// `{prefix}_RETRY_BASE_DELAY_MS` when they are set.
+    pub fn from_env(prefix: &str, default: ClientConfig) -> Self {
+        let var = |name: &str| std::env::var(format!("{}_{}", prefix, name)).ok();
+        let number = |name: &str| {
+            var(name).map(|value| {
+                value
+                    .parse::<u64>()
+                    .unwrap_or_else(|_| panic!("{}_{} must be a non-negative integer", prefix, name))
+            })
+        };
+        let millis = |name: &str| number(name).map(Duration::from_millis);
+        let count = |name: &str| {
+            number(name).map(|n| {
+                u32::try_from(n).unwrap_or_else(|_| panic!("{}_{} is too large", prefix, name))
+            })
+        };
+        ClientConfig {
+            name: default.name,
+            base_url: var("BASE_URL")
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or(default.base_url),
+            timeout: millis("TIMEOUT_MS").unwrap_or(default.timeout),
+            user_agent: var("USER_AGENT").unwrap_or(default.user_agent),
+            wait_time: millis("WAIT_TIME_MS").unwrap_or(default.wait_time),
+            burst: count("BURST").unwrap_or(default.burst),
+            max_retries: count("MAX_RETRIES").unwrap_or(default.max_retries),
+            retry_base_delay: millis("RETRY_BASE_DELAY_MS").unwrap_or(default.retry_base_delay),
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    pub fn build_http_client(&self) -> reqwest::Client {
+        reqwest::Client::builder()
+            .timeout(self.timeout)
+            .user_agent(&self.user_agent)
+            .build()
+            .expect("Failed to create HTTP client")
+    }
+}
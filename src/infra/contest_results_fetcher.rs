@@ -0,0 +1,279 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use flate2::read::GzDecoder;
+use std::collections::HashMap;
+use std::io::Read;
+use crate::domain::entity::{ContestResult, ContestType, FinishedContest};
+
+use super::client_config::ClientConfig;
+use super::http_client::HttpClient;
+
+static CONTEST_LIST_BASE_URL: &str = "https://kenkoooo.com/atcoder";
+static CONTEST_LIST_WAIT_TIME_MS: u64 = 1000;
+// Matches a row of AtCoder's contest tables, whose icon tells the rating scale
+static CONTEST_ROW_PATTERN: &str = r#"title="(Algorithm|Heuristic)"[^\n]*?<a href="/contests/([^"/?]+)">"#;
+
+pub struct ContestResultsFetcherImpl {
+    atcoder_http_client: HttpClient,
+    atcoder_config: ClientConfig,
+    contest_list_http_client: HttpClient,
+    contest_list_config: ClientConfig,
+}
+
+/*
+  {
+    "id": "abc300",
+    "start_epoch_second": 1682769600,
+    "duration_second": 6000,
+    "title": "AtCoder Beginner Contest 300",
+    "rate_change": " ~ 1999"
+  },
+*/
+#[derive(Debug, Clone, serde::Deserialize)]
+struct ContestDto {
+    id: String,
+    start_epoch_second: i64,
+    duration_second: i64,
+    rate_change: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct ContestResultDto {
+    #[serde(rename = "IsRated")]
+    is_rated: bool,
+    #[serde(rename = "Place")]
+    place: i32,
+    #[serde(rename = "OldRating")]
+    old_rating: i32,
+    #[serde(rename = "NewRating")]
+    new_rating: i32,
+    #[serde(rename = "Performance")]
+    performance: i32,
+    #[serde(rename = "ContestScreenName")]
+    contest_screen_name: String,
+    #[serde(rename = "ContestName")]
+    contest_name: String,
+    #[serde(rename = "EndTime")]
+    end_time: String,
+    #[serde(rename = "UserScreenName")]
+    user_screen_name: String,
+}
+
+impl ContestResultDto {
+    fn into_entity(self) -> Result<(String, ContestResult)> {
+        let end_time = chrono::DateTime::parse_from_rfc3339(&self.end_time)
+            .map_err(|e| anyhow::anyhow!("Failed to parse end time {}: {}", self.end_time, e))?
+            .with_timezone(&chrono::Utc);
+        Ok((
+            self.user_screen_name,
+            ContestResult {
+                is_rated: self.is_rated,
+                place: self.place,
+                old_rating: self.old_rating,
+                new_rating: self.new_rating,
+                diff: self.new_rating - self.old_rating,
+                performance: self.performance,
+                contest_screen_name: self.contest_screen_name,
+                contest_name: self.contest_name,
+                end_time,
+            },
+        ))
+    }
+}
+
+#[async_trait]
+impl crate::domain::contest_results_fetcher::ContestResultsFetcher for ContestResultsFetcherImpl {
+    async fn get_finished_contests(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<FinishedContest>> {
+        let url = self.contest_list_config.url("/resources/contests.json");
+        tracing::info!("Fetching from {}", url);
+        let contests: Vec<ContestDto> = Self::get_json(&self.contest_list_http_client, &url).await?;
+        let contests = contests
+            .into_iter()
+            // Unrated contests have "-" as their rating range
+            .filter(|contest| contest.rate_change.trim() != "-")
+            .filter_map(|contest| {
+                let end_time = chrono::DateTime::from_timestamp(
+                    contest.start_epoch_second + contest.duration_second,
+                    0,
+                )?;
+                Some((contest.id, end_time))
+            })
+            .filter(|(_, end_time)| since < *end_time && *end_time <= until)
+            .collect::<Vec<_>>();
+        if contests.is_empty() {
+            return Ok(vec![]);
+        }
+        let contest_types = self.get_contest_types().await?;
+        let mut finished = contests
+            .into_iter()
+            .filter_map(|(contest_id, end_time)| {
+                let Some(contest_type) = contest_types.get(&contest_id) else {
+                    tracing::warn!("Skipping {} because it is not in the contest archive", contest_id);
+                    return None;
+                };
+                Some(FinishedContest {
+                    contest_id,
+                    contest_type: *contest_type,
+                    end_time,
+                })
+            })
+            .collect::<Vec<_>>();
+        finished.sort_by_key(|contest| contest.end_time);
+        Ok(finished)
+    }
+
+    async fn get_results(&self, contest: &FinishedContest) -> Result<HashMap<String, ContestResult>> {
+        let url = self.atcoder_config.url(&format!("/contests/{}/results/json", contest.contest_id));
+        tracing::info!("Fetching from {}", url);
+        let data: Vec<ContestResultDto> = Self::get_json(&self.atcoder_http_client, &url).await?;
+        let results = data
+            .into_iter()
+            .map(ContestResultDto::into_entity)
+            .collect::<Result<HashMap<_, _>>>()?;
+        Ok(results)
+    }
+}
+
+impl ContestResultsFetcherImpl {
+    pub fn new(atcoder_config: ClientConfig, contest_list_config: ClientConfig) -> Self {
+        ContestResultsFetcherImpl {
+            atcoder_http_client: HttpClient::new(&atcoder_config),
+            atcoder_config,
+            contest_list_http_client: HttpClient::new(&contest_list_config),
+            contest_list_config,
+        }
+    }
+
+    pub fn default_contest_list_config() -> ClientConfig {
+        ClientConfig::new("AtCoder Problems", CONTEST_LIST_BASE_URL, std::time::Duration::from_millis(CONTEST_LIST_WAIT_TIME_MS))
+    }
+
+    /// Reads the rating scale of the recent contests from AtCoder's contest archive.
+    async fn get_contest_types(&self) -> Result<HashMap<String, ContestType>> {
+        let url = self.atcoder_config.url("/contests/archive?lang=en");
+        tracing::info!("Fetching from {}", url);
+        let response = self.atcoder_http_client
+            .send(|client| client.get(&url))
+            .await?;
+        if response.status() != reqwest::StatusCode::OK {
+            return Err(anyhow::anyhow!("Failed to fetch contest archive: {}", response.status()));
+        }
+        let html = response
+            .text()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read response: {}", e))?;
+        Ok(parse_contest_types(&html))
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(http_client: &HttpClient, url: &str) -> Result<T> {
+        let response = http_client
+            .send(|client| client.get(url).header("Accept-Encoding", "gzip"))
+            .await?;
+        if response.status() != reqwest::StatusCode::OK {
+            return Err(anyhow::anyhow!("Failed to fetch data: {}", response.status()));
+        }
+        let is_gzipped = response
+            .headers()
+            .get("Content-Encoding")
+            .is_some_and(|v| v.as_bytes() == b"gzip");
+        let bytes = response.bytes().await?;
+        let mut s = String::new();
+        if is_gzipped {
+            GzDecoder::new(bytes.as_ref())
+                .read_to_string(&mut s)
+                .map_err(|e| anyhow::anyhow!("Failed to decompress response: {}", e))?;
+        } else {
+            s = String::from_utf8(bytes.to_vec())?;
+        }
+        let data = serde_json::from_str(&s)
+            .map_err(|e| anyhow::anyhow!("Failed to parse JSON: {}", e))?;
+        Ok(data)
+    }
+}
+
+fn parse_contest_types(html: &str) -> HashMap<String, ContestType> {
+    let row_reg = regex::Regex::new(CONTEST_ROW_PATTERN)
+        .expect("Failed to compile regex");
+    row_reg
+        .captures_iter(html)
+        .map(|captures| {
+            let contest_type = match &captures[1] {
+                "Heuristic" => ContestType::Heuristic,
+                _ => ContestType::Algorithm,
+            };
+            (captures[2].to_string(), contest_type)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::contest_results_fetcher::ContestResultsFetcher as _;
+    use crate::testing::fake_server::{FakeServer, atcoder_router, contest_list_entry, contest_list_router};
+
+    #[test]
+    fn test_parse_contest_types() {
+        let contest_types = parse_contest_types(include_str!("../testing/fixtures/atcoder/archive.html"));
+        assert_eq!(contest_types.len(), 4);
+        assert_eq!(contest_types["abc400"], ContestType::Algorithm);
+        assert_eq!(contest_types["ahc045"], ContestType::Heuristic);
+        // Heuristic contests are not all named ahc
+        assert_eq!(contest_types["asprocon11"], ContestType::Heuristic);
+    }
+
+    #[tokio::test]
+    #[ignore = "hits the real AtCoder and AtCoder Problems APIs"]
+    async fn test_get_results() {
+        let fetcher = ContestResultsFetcherImpl::new(
+            crate::infra::detail_updater::DetailUpdaterImpl::default_config(),
+            ContestResultsFetcherImpl::default_contest_list_config(),
+        );
+        let since = chrono::DateTime::parse_from_rfc3339("2023-04-29T00:00:00+09:00")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let until = chrono::DateTime::parse_from_rfc3339("2023-04-30T00:00:00+09:00")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let contests = fetcher
+            .get_finished_contests(since, until)
+            .await
+            .expect("Failed to get finished contests");
+        let abc300 = contests
+            .iter()
+            .find(|contest| contest.contest_id == "abc300")
+            .expect("abc300 not found");
+        let results = fetcher.get_results(abc300).await.expect("Failed to get results");
+        assert!(!results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_results_from_fake_server() {
+        let now = chrono::Utc::now();
+        let hours_ago = |hours| now - chrono::Duration::hours(hours);
+        let atcoder = FakeServer::start(atcoder_router()).await;
+        let contest_list = FakeServer::start(contest_list_router(vec![
+            contest_list_entry("abc300", hours_ago(24 * 365), " ~ 1999"),
+            contest_list_entry("abc400", hours_ago(1), " ~ 1999"),
+            contest_list_entry("ahc045", hours_ago(2), "All"),
+            contest_list_entry("practice", hours_ago(3), "-"),
+        ]))
+            .await;
+        let fetcher = ContestResultsFetcherImpl::new(atcoder.config(), contest_list.config());
+        let contests = fetcher.get_finished_contests(hours_ago(24), now).await.unwrap();
+        let ids = contests
+            .iter()
+            .map(|contest| contest.contest_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["ahc045", "abc400"]);
+        assert_eq!(contests[0].contest_type, ContestType::Heuristic);
+        let results = fetcher.get_results(&contests[1]).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results["Bob_AC"].new_rating, 1701);
+        assert_eq!(results["Bob_AC"].contest_screen_name, "abc400.contest.atcoder.jp");
+    }
+}
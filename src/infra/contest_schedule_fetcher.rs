@@ -0,0 +1,114 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use crate::domain::entity::Contest;
+
+use super::client_config::ClientConfig;
+use super::http_client::HttpClient;
+
+// Only the contests with a rating, e.g. abc401 or ahc046
+static CONTEST_ID_PATTERN: &str = r"^(abc|arc|agc|ahc)\d+$";
+
+pub struct ContestScheduleFetcherImpl {
+    http_client: HttpClient,
+    config: ClientConfig,
+}
+
+#[async_trait]
+impl crate::domain::contest_schedule_fetcher::ContestScheduleFetcher for ContestScheduleFetcherImpl {
+    async fn get_upcoming_contests(&self) -> Result<Vec<Contest>> {
+        let url = self.config.url("/contests/?lang=en");
+        tracing::info!("Fetching from {}", url);
+        let response = self.http_client
+            .send(|client| client.get(&url))
+            .await?;
+        if response.status() != reqwest::StatusCode::OK {
+            return Err(anyhow::anyhow!("Failed to fetch contests: {}", response.status()));
+        }
+        let html = response
+            .text()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read response: {}", e))?;
+        parse_upcoming_contests(&html, &self.config)
+    }
+}
+
+impl ContestScheduleFetcherImpl {
+    /// Scrapes the contest list of the AtCoder site `atcoder_config` points to.
+    pub fn new(atcoder_config: ClientConfig) -> Self {
+        ContestScheduleFetcherImpl {
+            http_client: HttpClient::new(&atcoder_config),
+            config: atcoder_config,
+        }
+    }
+}
+
+/// Reads the "Upcoming Contests" table of `/contests/`.
+fn parse_upcoming_contests(html: &str, config: &ClientConfig) -> Result<Vec<Contest>> {
+    let start = html
+        .find(r#"id="contest-table-upcoming""#)
+        .ok_or_else(|| anyhow::anyhow!("Upcoming contests table not found"))?;
+    let table = &html[start..];
+    let table = &table[..table.find("</tbody>").unwrap_or(table.len())];
+    let time_reg = regex::Regex::new(r"<time[^>]*>([^<]+)</time>")
+        .expect("Failed to compile regex");
+    let link_reg = regex::Regex::new(r#"<a href="/contests/([^"/]+)">([^<]+)</a>"#)
+        .expect("Failed to compile regex");
+    let duration_reg = regex::Regex::new(r"<td[^>]*>\s*(\d+):(\d{2})\s*</td>")
+        .expect("Failed to compile regex");
+    let id_reg = regex::Regex::new(CONTEST_ID_PATTERN)
+        .expect("Failed to compile regex");
+    let mut contests = vec![];
+    for row in table.split("<tr>").skip(1) {
+        let (Some(time), Some(link), Some(duration)) = (
+            time_reg.captures(row),
+            link_reg.captures(row),
+            duration_reg.captures(row),
+        ) else {
+            continue;
+        };
+        let contest_id = link[1].to_string();
+        if !id_reg.is_match(&contest_id) {
+            continue;
+        }
+        let start_time = chrono::DateTime::parse_from_str(&time[1], "%Y-%m-%d %H:%M:%S%z")
+            .map_err(|e| anyhow::anyhow!("Failed to parse start time {}: {}", &time[1], e))?
+            .with_timezone(&chrono::Utc);
+        let hours = duration[1].parse::<i64>()?;
+        let minutes = duration[2].parse::<i64>()?;
+        contests.push(Contest {
+            contest_url: config.url(&format!("/contests/{}", contest_id)),
+            contest_id,
+            contest_name: link[2].trim().to_string(),
+            start_time,
+            end_time: start_time + chrono::Duration::minutes(hours * 60 + minutes),
+        });
+    }
+    contests.sort_by_key(|contest| contest.start_time);
+    Ok(contests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::contest_schedule_fetcher::ContestScheduleFetcher as _;
+    use crate::testing::fake_server::{FakeServer, atcoder_router};
+
+    #[tokio::test]
+    async fn test_get_upcoming_contests_from_fake_server() {
+        let server = FakeServer::start(atcoder_router()).await;
+        let fetcher = ContestScheduleFetcherImpl::new(server.config());
+        let contests = fetcher.get_upcoming_contests().await.unwrap();
+        let ids = contests
+            .iter()
+            .map(|contest| contest.contest_id.as_str())
+            .collect::<Vec<_>>();
+        // Active, recent and unrated contests are left out
+        assert_eq!(ids, vec!["abc401", "arc196", "ahc046", "agc072"]);
+        let abc401 = &contests[0];
+        assert_eq!(abc401.contest_name, "AtCoder Beginner Contest 401");
+        assert_eq!(abc401.contest_url, format!("{}/contests/abc401", server.base_url));
+        assert_eq!(abc401.start_time.to_rfc3339(), "2025-04-19T12:00:00+00:00");
+        assert_eq!(abc401.end_time - abc401.start_time, chrono::Duration::minutes(100));
+        assert_eq!(contests[2].end_time - contests[2].start_time, chrono::Duration::hours(240));
+    }
+}
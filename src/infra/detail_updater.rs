@@ -4,11 +4,17 @@ use flate2::read::GzDecoder;
 use async_trait::async_trait;
 use std::io::Read;
 use std::collections::HashMap;
+use crate::domain::entity::{AcDetailedInfo, FetchFailure, PartialFetch};
 
+use super::client_config::ClientConfig;
+use super::http_client::HttpClient;
+
+static ATCODER_BASE_URL: &str = "https://atcoder.jp";
 static ATCODER_WAIT_TIME_MS: u64 = 1000;
 
 pub struct DetailUpdaterImpl {
-    http_client: reqwest::Client,
+    http_client: HttpClient,
+    config: ClientConfig,
 }
 
 /*
@@ -39,63 +45,80 @@ struct ContestResultDto {
     EndTime: String,
 }
 
+impl ContestResultDto {
+    fn into_entity(self) -> Result<crate::domain::entity::ContestResult> {
+        let end_time = chrono::DateTime::parse_from_rfc3339(&self.EndTime)
+            .map_err(|e| anyhow::anyhow!("Failed to parse end time {}: {}", self.EndTime, e))?
+            .with_timezone(&chrono::Utc);
+        Ok(crate::domain::entity::ContestResult {
+            is_rated: self.IsRated,
+            place: self.Place,
+            old_rating: self.OldRating,
+            new_rating: self.NewRating,
+            diff: self.NewRating - self.OldRating,
+            performance: self.Performance,
+            contest_screen_name: self.ContestScreenName,
+            contest_name: self.ContestName,
+            end_time,
+        })
+    }
+}
+
 #[async_trait]
 impl crate::domain::detail_updater::DetailedInfoUpdater for DetailUpdaterImpl {
     async fn get(
         &self,
         usernames: Vec<String>,
-    ) -> Result<HashMap<String, crate::domain::entity::AcDetailedInfo>> {
+    ) -> Result<PartialFetch<HashMap<String, AcDetailedInfo>>> {
         tracing::info!("Starting to fetch from atcoder");
         let mut results = HashMap::new();
+        let mut failures = vec![];
         for username in usernames {
-            let data = self.get_inner(&username, false).await?;
-            let heur_data = self.get_inner(&username, true).await?;
-            let contest_results: Vec<crate::domain::entity::ContestResult> = data
-                .into_iter()
-                .map(|dto| crate::domain::entity::ContestResult {
-                    is_rated: dto.IsRated,
-                    place: dto.Place,
-                    old_rating: dto.OldRating,
-                    new_rating: dto.NewRating,
-                    diff: dto.NewRating - dto.OldRating,
-                    performance: dto.Performance,
-                    contest_screen_name: dto.ContestScreenName,
-                    contest_name: dto.ContestName,
-                    end_time: dto.EndTime,
-                })
-                .collect();
-            let heur_results: Vec<crate::domain::entity::ContestResult> = heur_data
-                .into_iter()
-                .map(|dto| crate::domain::entity::ContestResult {
-                    is_rated: dto.IsRated,
-                    place: dto.Place,
-                    old_rating: dto.OldRating,
-                    new_rating: dto.NewRating,
-                    diff: dto.NewRating - dto.OldRating,
-                    performance: dto.Performance,
-                    contest_screen_name: dto.ContestScreenName,
-                    contest_name: dto.ContestName,
-                    end_time: dto.EndTime,
-                })
-                .collect();
-            results.insert(
-                username.clone(),
-                crate::domain::entity::AcDetailedInfo {
-                    algo_rating: contest_results,
-                    heur_rating: heur_results,
-                },
-            );
-            // Sleep to avoid hitting the rate limit
-            tokio::time::sleep(std::time::Duration::from_millis(ATCODER_WAIT_TIME_MS)).await;
+            match self.get_user(&username).await {
+                Ok(info) => {
+                    results.insert(username, info);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to fetch {} from atcoder: {}", username, e);
+                    failures.push(FetchFailure {
+                        key: username,
+                        reason: e.to_string(),
+                    });
+                }
+            }
         }
-        Ok(results)
+        Ok(PartialFetch {
+            fetched: results,
+            failures,
+        })
     }
 }
 
 impl DetailUpdaterImpl {
-    pub fn new() -> Self {
-        let http_client = reqwest::Client::new();
-        DetailUpdaterImpl { http_client }
+    pub fn new(config: ClientConfig) -> Self {
+        let http_client = HttpClient::new(&config);
+        DetailUpdaterImpl { http_client, config }
+    }
+
+    pub fn default_config() -> ClientConfig {
+        ClientConfig::new("AtCoder", ATCODER_BASE_URL, std::time::Duration::from_millis(ATCODER_WAIT_TIME_MS))
+    }
+
+    async fn get_user(&self, username: &str) -> Result<AcDetailedInfo> {
+        let data = self.get_inner(username, false).await?;
+        let heur_data = self.get_inner(username, true).await?;
+        let contest_results = data
+            .into_iter()
+            .map(ContestResultDto::into_entity)
+            .collect::<Result<Vec<_>>>()?;
+        let heur_results = heur_data
+            .into_iter()
+            .map(ContestResultDto::into_entity)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(AcDetailedInfo {
+            algo_rating: contest_results,
+            heur_rating: heur_results,
+        })
     }
 
     async fn get_inner(
@@ -108,14 +131,11 @@ impl DetailUpdaterImpl {
         } else {
             ""
         };
-        let url = format!("https://atcoder.jp/users/{}/history/json{}", username, query_param);
+        let url = self.config.url(&format!("/users/{}/history/json{}", username, query_param));
         tracing::info!("Fetching from {}", url);
         let response = self.http_client
-            .get(&url)
-            .header("Accept-Encoding", "gzip")
-            .send()
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to send request: {}", e))?;
+            .send(|client| client.get(&url).header("Accept-Encoding", "gzip"))
+            .await?;
         match response.status() {
             reqwest::StatusCode::OK => {
                 if Some("gzip") != response.headers().get("Content-Encoding").map(|v| v.to_str().unwrap()) {
@@ -143,17 +163,20 @@ mod tests {
     use super::*;
     use tokio;
     use crate::domain::detail_updater::DetailedInfoUpdater as _;
+    use crate::testing::fake_server::{FakeServer, atcoder_router};
+
     #[tokio::test]
+    #[ignore = "hits the real AtCoder API"]
     async fn test_get() {
-        let updater = DetailUpdaterImpl::new();
+        let updater = DetailUpdaterImpl::new(DetailUpdaterImpl::default_config());
         let usernames = vec!["Dye8128".to_string(), "chokudai".to_string()];
         let result = updater
             .get(usernames.clone())
             .await;
         match result {
             Ok(data) => {
-                assert_eq!(data.len(), usernames.len());
-                for (username, info) in data.iter() {
+                assert_eq!(data.fetched.len(), usernames.len());
+                for (username, info) in data.fetched.iter() {
                     println!("Username: {}", username);
                     for contest in &info.algo_rating {
                         println!("Contest: {}, Place: {}", contest.contest_name, contest.place);
@@ -165,4 +188,22 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_get_from_fake_server() {
+        let server = FakeServer::start(atcoder_router()).await;
+        let updater = DetailUpdaterImpl::new(server.config());
+        let result = updater
+            .get(vec!["alice_ac".to_string(), "ghost_ac".to_string()])
+            .await
+            .unwrap();
+        let alice = &result.fetched["alice_ac"];
+        assert_eq!(alice.algo_rating.len(), 2);
+        assert_eq!(alice.algo_rating[1].new_rating, 412);
+        assert_eq!(alice.algo_rating[1].diff, 352);
+        assert_eq!(alice.heur_rating.len(), 1);
+        assert!(!result.fetched.contains_key("ghost_ac"));
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].key, "ghost_ac");
+    }
 }
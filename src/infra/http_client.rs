@@ -0,0 +1,312 @@
+use anyhow::Result;
+use rand::Rng;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use super::client_config::ClientConfig;
+
+static MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+// The host and the `wait_time` and `burst` of its config
+type BucketKey = (String, Duration, u32);
+
+// Every client talking to the same host at the same rate shares one bucket
+static BUCKETS: LazyLock<std::sync::Mutex<HashMap<BucketKey, Arc<TokenBucket>>>> =
+    LazyLock::new(Default::default);
+
+/// Allows one request per `interval` on average, with bursts of up to `capacity`.
+struct TokenBucket {
+    interval: Duration,
+    capacity: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    fn new(interval: Duration, capacity: u32) -> Self {
+        let capacity = f64::from(capacity.max(1));
+        TokenBucket {
+            interval,
+            capacity,
+            state: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    async fn acquire(&self) {
+        if self.interval.is_zero() {
+            return;
+        }
+        // Holding the lock while sleeping keeps waiters in FIFO order
+        let mut state = self.state.lock().await;
+        loop {
+            let (tokens, last) = *state;
+            let now = Instant::now();
+            let refilled = (tokens + now.duration_since(last).as_secs_f64() / self.interval.as_secs_f64())
+                .min(self.capacity);
+            if refilled >= 1.0 {
+                *state = (refilled - 1.0, now);
+                return;
+            }
+            *state = (refilled, now);
+            tokio::time::sleep(self.interval.mul_f64(1.0 - refilled)).await;
+        }
+    }
+}
+
+/// Outbound HTTP shared by every upstream client.
+///
+/// Requests are rate limited per host and rate, and timeouts, connection errors, 429 and 5xx
+/// responses are retried with exponential backoff and full jitter, honoring `Retry-After`.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    bucket: Arc<TokenBucket>,
+    // The service name, labelling the upstream metrics
+    upstream: String,
+    max_retries: u32,
+    retry_base_delay: Duration,
+}
+
+impl HttpClient {
+    pub fn new(config: &ClientConfig) -> Self {
+        let host = reqwest::Url::parse(&config.base_url)
+            .ok()
+            .and_then(|url| Some(format!("{}:{}", url.host_str()?, url.port_or_known_default()?)))
+            .unwrap_or_else(|| config.base_url.clone());
+        let bucket = BUCKETS
+            .lock()
+            .unwrap()
+            .entry((host, config.wait_time, config.burst))
+            .or_insert_with(|| Arc::new(TokenBucket::new(config.wait_time, config.burst)))
+            .clone();
+        HttpClient {
+            client: config.build_http_client(),
+            bucket,
+            upstream: config.name.clone(),
+            max_retries: config.max_retries,
+            retry_base_delay: config.retry_base_delay,
+        }
+    }
+
+    /// The underlying client, for libraries that issue requests themselves.
+    /// Wrap such calls in [`HttpClient::run`].
+    pub fn inner(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// Sends the request built by `build`, retrying transient failures.
+    ///
+    /// Responses with other error statuses such as 404 are returned as is, and so is a
+    /// retryable response whose `Retry-After` is longer than we are willing to wait.
+    pub async fn send<F>(&self, build: F) -> Result<reqwest::Response>
+    where
+        F: Fn(&reqwest::Client) -> reqwest::RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            self.bucket.acquire().await;
+            let request = build(&self.client);
+            let result = request.send().await;
+            self.record_attempt(result.as_ref().map_or(true, |response| is_retryable_status(response.status())));
+            match result {
+                Ok(response) if is_retryable_status(response.status()) && attempt < self.max_retries => {
+                    let delay = match retry_after(&response) {
+                        Some(delay) if delay > MAX_RETRY_DELAY => {
+                            tracing::warn!(
+                                "{} returned {} with Retry-After {:?}, giving up",
+                                response.url(),
+                                response.status(),
+                                delay
+                            );
+                            return Ok(response);
+                        }
+                        Some(delay) => delay,
+                        None => self.backoff(attempt),
+                    };
+                    tracing::warn!(
+                        "{} returned {}, retrying in {:?}",
+                        response.url(),
+                        response.status(),
+                        delay
+                    );
+                    self.record_retry();
+                    tokio::time::sleep(delay).await;
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if is_retryable_error(&e) && attempt < self.max_retries => {
+                    let delay = self.backoff(attempt);
+                    tracing::warn!("Request failed, retrying in {:?}: {}", delay, e);
+                    self.record_retry();
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(anyhow::anyhow!("Failed to send request: {}", e)),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Runs `op` under the same rate limit and retry policy as [`HttpClient::send`],
+    /// for requests made by generated API clients.
+    ///
+    /// The generated clients do not expose response headers, so `Retry-After` cannot be
+    /// honored here and retries always use the backoff.
+    pub async fn run<T, E, F, Fut>(&self, mut op: F, is_retryable: impl Fn(&E) -> bool) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: std::fmt::Display,
+    {
+        let mut attempt = 0;
+        loop {
+            self.bucket.acquire().await;
+            let result = op().await;
+            self.record_attempt(result.is_err());
+            match result {
+                Err(e) if is_retryable(&e) && attempt < self.max_retries => {
+                    let delay = self.backoff(attempt);
+                    tracing::warn!("Request failed, retrying in {:?}: {}", delay, e);
+                    self.record_retry();
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    fn record_attempt(&self, is_error: bool) {
+        metrics::counter!("upstream_requests_total", "upstream" => self.upstream.clone()).increment(1);
+        if is_error {
+            metrics::counter!("upstream_errors_total", "upstream" => self.upstream.clone()).increment(1);
+        }
+    }
+
+    fn record_retry(&self) {
+        metrics::counter!("upstream_retries_total", "upstream" => self.upstream.clone()).increment(1);
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self.retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_RETRY_DELAY);
+        ceiling.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+    }
+}
+
+pub fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+pub fn is_retryable_error(e: &reqwest::Error) -> bool {
+    e.is_timeout() || e.is_connect() || e.status().is_some_and(is_retryable_status)
+}
+
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?;
+    let delay = match value.trim().parse::<u64>() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => {
+            let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+            (at.with_timezone(&chrono::Utc) - chrono::Utc::now())
+                .to_std()
+                .unwrap_or(Duration::ZERO)
+        }
+    };
+    Some(delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use axum::{Router, http::StatusCode, routing::get};
+    use crate::testing::fake_server::FakeServer;
+
+    fn flaky_router(failures: u32, status: StatusCode, retry_after: Option<&'static str>) -> (Router, Arc<AtomicU32>) {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let router = Router::new().route(
+            "/",
+            get(move || {
+                let counter = counter.clone();
+                async move {
+                    let call = counter.fetch_add(1, Ordering::SeqCst);
+                    let mut headers = axum::http::HeaderMap::new();
+                    if let Some(retry_after) = retry_after {
+                        headers.insert("Retry-After", retry_after.parse().unwrap());
+                    }
+                    if call < failures {
+                        (status, headers, "")
+                    } else {
+                        (StatusCode::OK, headers, "ok")
+                    }
+                }
+            }),
+        );
+        (router, calls)
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let (router, calls) = flaky_router(2, StatusCode::SERVICE_UNAVAILABLE, None);
+        let server = FakeServer::start(router).await;
+        let client = HttpClient::new(&server.config());
+        let recorder = metrics_exporter_prometheus::PrometheusBuilder::new().build_recorder();
+        let metrics = recorder.handle();
+        let _guard = metrics::set_default_local_recorder(&recorder);
+        let response = client.send(|c| c.get(&server.base_url)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        let rendered = metrics.render();
+        assert!(rendered.contains(r#"upstream_requests_total{upstream="fake"} 3"#));
+        assert!(rendered.contains(r#"upstream_errors_total{upstream="fake"} 2"#));
+        assert!(rendered.contains(r#"upstream_retries_total{upstream="fake"} 2"#));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let (router, calls) = flaky_router(100, StatusCode::TOO_MANY_REQUESTS, Some("0"));
+        let server = FakeServer::start(router).await;
+        let config = server.config();
+        let client = HttpClient::new(&config);
+        let response = client.send(|c| c.get(&server.base_url)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(calls.load(Ordering::SeqCst), config.max_retries + 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_on_long_retry_after() {
+        let (router, calls) = flaky_router(1, StatusCode::TOO_MANY_REQUESTS, Some("120"));
+        let server = FakeServer::start(router).await;
+        let client = HttpClient::new(&server.config());
+        let response = client.send(|c| c.get(&server.base_url)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_client_errors() {
+        let (router, calls) = flaky_router(1, StatusCode::NOT_FOUND, None);
+        let server = FakeServer::start(router).await;
+        let client = HttpClient::new(&server.config());
+        let response = client.send(|c| c.get(&server.base_url)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_spaces_requests() {
+        let bucket = TokenBucket::new(Duration::from_millis(50), 1);
+        let start = Instant::now();
+        for _ in 0..3 {
+            bucket.acquire().await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
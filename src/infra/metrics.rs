@@ -0,0 +1,59 @@
+use anyhow::Result;
+use std::time::Duration;
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+
+// Drains histogram samples between scrapes so they do not pile up
+static UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+static HTTP_DURATION_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+// A full update waits on rate limited upstreams for minutes
+static UPDATE_DURATION_BUCKETS: [f64; 9] = [10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 1800.0, 3600.0];
+
+/// Installs the global Prometheus recorder and returns the handle that renders `/metrics`.
+///
+/// Must be called once, inside the Tokio runtime.
+pub fn install_recorder() -> Result<PrometheusHandle> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Full("http_request_duration_seconds".to_string()), &HTTP_DURATION_BUCKETS)
+        .and_then(|builder| {
+            builder.set_buckets_for_metric(Matcher::Full("update_duration_seconds".to_string()), &UPDATE_DURATION_BUCKETS)
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to configure metrics: {}", e))?
+        .install_recorder()
+        .map_err(|e| anyhow::anyhow!("Failed to install metrics recorder: {}", e))?;
+    describe();
+    let upkeep_handle = handle.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(UPKEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            upkeep_handle.run_upkeep();
+        }
+    });
+    Ok(handle)
+}
+
+fn describe() {
+    metrics::describe_counter!("http_requests_total", "HTTP requests served, by method, route and status");
+    metrics::describe_histogram!(
+        "http_request_duration_seconds",
+        metrics::Unit::Seconds,
+        "Time to serve an HTTP request, by method and route"
+    );
+    metrics::describe_histogram!(
+        "update_duration_seconds",
+        metrics::Unit::Seconds,
+        "Time taken by an attempt of the update job, by result"
+    );
+    metrics::describe_gauge!(
+        "update_last_success_timestamp_seconds",
+        metrics::Unit::Seconds,
+        "Unix time at which the update job last succeeded"
+    );
+    metrics::describe_counter!("upstream_requests_total", "Requests sent to an upstream host, retries included");
+    metrics::describe_counter!(
+        "upstream_errors_total",
+        "Requests to an upstream host that failed or got a 429 or 5xx response"
+    );
+    metrics::describe_counter!("upstream_retries_total", "Requests to an upstream host that were retried");
+    metrics::describe_counter!("users_written_total", "User rows written to the database");
+}
@@ -0,0 +1,189 @@
+use anyhow::Result;
+use sqlx::MySqlPool;
+
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+// Append new migrations at the end with the next version number.
+// Never edit a migration that has already been released.
+static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "create_users",
+        sql: include_str!("../../migrations/0001_create_users.sql"),
+    },
+    Migration {
+        version: 2,
+        description: "create_contest_results",
+        sql: include_str!("../../migrations/0002_create_contest_results.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "create_rating_snapshots",
+        sql: include_str!("../../migrations/0003_create_rating_snapshots.sql"),
+    },
+    Migration {
+        version: 4,
+        description: "add_users_last_fetch_error",
+        sql: include_str!("../../migrations/0004_add_users_last_fetch_error.sql"),
+    },
+    Migration {
+        version: 5,
+        description: "create_update_runs",
+        sql: include_str!("../../migrations/0005_create_update_runs.sql"),
+    },
+    Migration {
+        version: 6,
+        description: "add_update_runs_digest_posted_at",
+        sql: include_str!("../../migrations/0006_add_update_runs_digest_posted_at.sql"),
+    },
+    Migration {
+        version: 7,
+        description: "create_contests",
+        sql: include_str!("../../migrations/0007_create_contests.sql"),
+    },
+    Migration {
+        version: 8,
+        description: "create_contest_reminders",
+        sql: include_str!("../../migrations/0008_create_contest_reminders.sql"),
+    },
+    Migration {
+        version: 9,
+        description: "create_applied_contests",
+        sql: include_str!("../../migrations/0009_create_applied_contests.sql"),
+    },
+];
+
+pub struct Migrator {
+    pool: MySqlPool,
+}
+
+impl Migrator {
+    pub fn new(pool: MySqlPool) -> Self {
+        Migrator { pool }
+    }
+
+    /// Applies every pending migration in version order.
+    pub async fn run(&self) -> Result<()> {
+        self.ensure_bookkeeping_table().await?;
+        let applied = self.applied_versions().await?;
+        let pending = pending_migrations(MIGRATIONS, &applied)?;
+        if pending.is_empty() {
+            tracing::info!("Schema is up to date (version {})", latest_version(MIGRATIONS));
+            return Ok(());
+        }
+        for migration in pending {
+            tracing::info!("Applying migration {} ({})", migration.version, migration.description);
+            sqlx::raw_sql(migration.sql)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to apply migration {}: {}", migration.version, e))?;
+            sqlx::query(
+                "INSERT INTO schema_migrations (`version`, `description`, `applied_at`) VALUES (?, ?, ?)"
+            )
+                .bind(migration.version)
+                .bind(migration.description)
+                .bind(chrono::Utc::now())
+                .execute(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to record migration {}: {}", migration.version, e))?;
+        }
+        Ok(())
+    }
+
+    /// Fails unless the database schema is exactly the one this binary was built for.
+    pub async fn check(&self) -> Result<()> {
+        self.ensure_bookkeeping_table().await?;
+        let applied = self.applied_versions().await?;
+        let pending = pending_migrations(MIGRATIONS, &applied)?;
+        if !pending.is_empty() {
+            return Err(anyhow::anyhow!(
+                "{} migration(s) pending, run `algo-stats migrate` first",
+                pending.len()
+            ));
+        }
+        Ok(())
+    }
+
+    async fn ensure_bookkeeping_table(&self) -> Result<()> {
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS `schema_migrations` (
+                `version` BIGINT NOT NULL PRIMARY KEY,
+                `description` VARCHAR(255) NOT NULL,
+                `applied_at` DATETIME NOT NULL
+            );
+            "#
+        )
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create schema_migrations: {}", e))?;
+        Ok(())
+    }
+
+    async fn applied_versions(&self) -> Result<Vec<i64>> {
+        let versions = sqlx::query_scalar::<_, i64>(
+            "SELECT `version` FROM schema_migrations ORDER BY `version` ASC"
+        )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch applied migrations: {}", e))?;
+        Ok(versions)
+    }
+}
+
+fn latest_version(migrations: &[Migration]) -> i64 {
+    migrations.last().map(|m| m.version).unwrap_or(0)
+}
+
+fn pending_migrations<'a>(
+    migrations: &'a [Migration],
+    applied: &[i64],
+) -> Result<Vec<&'a Migration>> {
+    let latest = latest_version(migrations);
+    if let Some(newest_applied) = applied.iter().max()
+        && *newest_applied > latest
+    {
+        return Err(anyhow::anyhow!(
+            "Database schema version {} is newer than this binary supports ({})",
+            newest_applied,
+            latest
+        ));
+    }
+    Ok(migrations
+        .iter()
+        .filter(|m| !applied.contains(&m.version))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_are_strictly_increasing() {
+        for pair in MIGRATIONS.windows(2) {
+            assert!(pair[0].version < pair[1].version);
+        }
+        assert_eq!(MIGRATIONS[0].version, 1);
+    }
+
+    #[test]
+    fn test_pending_migrations() {
+        let pending = pending_migrations(MIGRATIONS, &[]).unwrap();
+        assert_eq!(pending.len(), MIGRATIONS.len());
+        let pending = pending_migrations(MIGRATIONS, &[1]).unwrap();
+        assert_eq!(pending[0].version, 2);
+        let all = MIGRATIONS.iter().map(|m| m.version).collect::<Vec<_>>();
+        assert!(pending_migrations(MIGRATIONS, &all).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_refuses_newer_database() {
+        let newer = latest_version(MIGRATIONS) + 1;
+        assert!(pending_migrations(MIGRATIONS, &[1, newer]).is_err());
+    }
+}
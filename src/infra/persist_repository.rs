@@ -1,6 +1,10 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use sqlx::MySqlPool;
+use crate::domain::entity::{
+    Contest, ContestParticipation, ContestResult, ContestType, FinishedContest, RatingAggregate, UpdateMode, UserGrouping,
+    UserQuery, UserSort, UserSortKey,
+};
 
 #[derive(Clone)]
 pub struct PersistRepositoryImpl {
@@ -13,6 +17,223 @@ impl PersistRepositoryImpl {
     }
 }
 
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct ContestResultRow {
+    is_rated: bool,
+    place: i32,
+    old_rating: i32,
+    new_rating: i32,
+    performance: i32,
+    contest_screen_name: String,
+    contest_name: String,
+    end_time: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ContestResultRow> for ContestResult {
+    fn from(row: ContestResultRow) -> Self {
+        ContestResult {
+            is_rated: row.is_rated,
+            place: row.place,
+            old_rating: row.old_rating,
+            new_rating: row.new_rating,
+            diff: row.new_rating - row.old_rating,
+            performance: row.performance,
+            contest_screen_name: row.contest_screen_name,
+            contest_name: row.contest_name,
+            end_time: row.end_time,
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct PendingContestRow {
+    contest_id: String,
+    contest_type: String,
+    end_time: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<PendingContestRow> for FinishedContest {
+    type Error = anyhow::Error;
+
+    fn try_from(row: PendingContestRow) -> Result<Self> {
+        Ok(FinishedContest {
+            contest_id: row.contest_id,
+            contest_type: row.contest_type.parse()?,
+            end_time: row.end_time,
+        })
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct ContestParticipationRow {
+    trap_account_name: String,
+    atcoder_account_name: String,
+    contest_type: String,
+    #[sqlx(flatten)]
+    result: ContestResultRow,
+}
+
+impl TryFrom<ContestParticipationRow> for ContestParticipation {
+    type Error = anyhow::Error;
+
+    fn try_from(row: ContestParticipationRow) -> Result<Self> {
+        Ok(ContestParticipation {
+            trap_account_name: row.trap_account_name,
+            atcoder_account_name: row.atcoder_account_name,
+            contest_type: row.contest_type.parse()?,
+            result: row.result.into(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct RatingAggregateRow {
+    group_key: Option<String>,
+    members: i64,
+    linked: i64,
+    rated: i64,
+    mean_rating: Option<f64>,
+    median_rating: Option<f64>,
+    max_rating: Option<i32>,
+}
+
+impl From<RatingAggregateRow> for RatingAggregate {
+    fn from(row: RatingAggregateRow) -> Self {
+        RatingAggregate {
+            group: row.group_key,
+            members: row.members,
+            linked: row.linked,
+            rated: row.rated,
+            mean_rating: row.mean_rating,
+            median_rating: row.median_rating,
+            max_rating: row.max_rating,
+        }
+    }
+}
+
+fn rating_column(contest_type: ContestType) -> &'static str {
+    match contest_type {
+        ContestType::Algorithm => "atcoder_rating",
+        ContestType::Heuristic => "heuristic_rating",
+    }
+}
+
+fn rating_aggregates_sql(contest_type: ContestType, grouping: UserGrouping) -> String {
+    let group_key = match grouping {
+        UserGrouping::All => "CAST(NULL AS CHAR)",
+        UserGrouping::Grade => "`grade`",
+        UserGrouping::AlgoTeam => "CASE WHEN `is_algo_team` THEN 'algoTeam' ELSE 'others' END",
+    };
+    // MySQL has no MEDIAN, so the middle one or two rows are averaged
+    format!(
+        r#"
+        WITH ranked AS (
+            SELECT
+                {group_key} AS group_key,
+                `{rating}` AS rating,
+                ROW_NUMBER() OVER (PARTITION BY {group_key} ORDER BY `{rating}`) AS position,
+                COUNT(*) OVER (PARTITION BY {group_key}) AS rated
+            FROM users
+            WHERE is_active AND `{rating}` > 0
+        ),
+        medians AS (
+            SELECT group_key, CAST(AVG(rating) AS DOUBLE) AS median_rating
+            FROM ranked
+            WHERE position IN (FLOOR((rated + 1) / 2), CEIL((rated + 1) / 2))
+            GROUP BY group_key
+        ),
+        aggregates AS (
+            SELECT
+                {group_key} AS group_key,
+                COUNT(*) AS members,
+                COUNT(`atcoder_account_name`) AS linked,
+                COUNT(CASE WHEN `{rating}` > 0 THEN 1 END) AS rated,
+                CAST(AVG(CASE WHEN `{rating}` > 0 THEN `{rating}` END) AS DOUBLE) AS mean_rating,
+                MAX(CASE WHEN `{rating}` > 0 THEN `{rating}` END) AS max_rating
+            FROM users
+            WHERE is_active
+            GROUP BY group_key
+        )
+        SELECT
+            aggregates.group_key,
+            aggregates.members,
+            aggregates.linked,
+            aggregates.rated,
+            aggregates.mean_rating,
+            medians.median_rating,
+            aggregates.max_rating
+        FROM aggregates
+        LEFT JOIN medians ON aggregates.group_key <=> medians.group_key
+        ORDER BY aggregates.group_key
+        "#,
+        group_key = group_key,
+        rating = rating_column(contest_type),
+    )
+}
+
+fn push_user_filters<'a>(query_builder: &mut sqlx::QueryBuilder<'a, sqlx::MySql>, query: &'a UserQuery) {
+    query_builder.push(" WHERE 1 = 1");
+    if let Some(grade) = &query.grade {
+        query_builder.push(" AND `grade` = ").push_bind(grade);
+    }
+    if let Some(is_algo_team) = query.is_algo_team {
+        query_builder.push(" AND `is_algo_team` = ").push_bind(is_algo_team);
+    }
+    if let Some(is_active) = query.is_active {
+        query_builder.push(" AND `is_active` = ").push_bind(is_active);
+    }
+    match query.has_atcoder {
+        Some(true) => query_builder.push(" AND `atcoder_account_name` IS NOT NULL"),
+        Some(false) => query_builder.push(" AND `atcoder_account_name` IS NULL"),
+        None => query_builder,
+    };
+    if let Some(min_rating) = query.min_atcoder_rating {
+        query_builder.push(" AND `atcoder_rating` >= ").push_bind(min_rating);
+    }
+    if let Some(min_rating) = query.min_heuristic_rating {
+        query_builder.push(" AND `heuristic_rating` >= ").push_bind(min_rating);
+    }
+}
+
+fn push_user_order(query_builder: &mut sqlx::QueryBuilder<'_, sqlx::MySql>, sort: Option<UserSort>) {
+    query_builder.push(" ORDER BY");
+    if let Some(sort) = sort {
+        let column = match sort.key {
+            UserSortKey::TrapAccountName => "trap_account_name",
+            UserSortKey::AtcoderRating => "atcoder_rating",
+            UserSortKey::HeuristicRating => "heuristic_rating",
+            UserSortKey::Grade => "grade",
+        };
+        let direction = if sort.descending { "DESC" } else { "ASC" };
+        // MySQL puts NULL first in ascending order
+        query_builder.push(format!(" `{0}` IS NULL, `{0}` {1},", column, direction));
+    }
+    query_builder.push(" `trap_account_name` ASC");
+}
+
+fn push_user_page(query_builder: &mut sqlx::QueryBuilder<'_, sqlx::MySql>, query: &UserQuery) {
+    match query.limit {
+        Some(limit) => query_builder.push(" LIMIT ").push_bind(limit),
+        // MySQL has no OFFSET without LIMIT, this is its documented way to say "no limit"
+        None if query.offset > 0 => query_builder.push(" LIMIT 18446744073709551615"),
+        None => return,
+    };
+    query_builder.push(" OFFSET ").push_bind(query.offset);
+}
+
+fn rated_contest_counts_query(atcoder_account_names: &[String]) -> sqlx::QueryBuilder<'_, sqlx::MySql> {
+    let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new(
+        "SELECT `atcoder_account_name`, `contest_type`, COUNT(*) FROM contest_results \
+        WHERE is_rated AND `atcoder_account_name` IN ("
+    );
+    let mut separated = query_builder.separated(", ");
+    for atcoder_account_name in atcoder_account_names {
+        separated.push_bind(atcoder_account_name);
+    }
+    query_builder.push(") GROUP BY atcoder_account_name, contest_type");
+    query_builder
+}
+
 #[async_trait]
 impl crate::domain::persist_repository::PersistRepository for PersistRepositoryImpl {
     async fn get_users(&self) -> Result<Vec<crate::domain::dto::User>> {
@@ -25,6 +246,63 @@ impl crate::domain::persist_repository::PersistRepository for PersistRepositoryI
         Ok(users)
     }
 
+    async fn find_users(&self, query: &UserQuery) -> Result<(Vec<crate::domain::dto::User>, i64)> {
+        let mut count_builder = sqlx::QueryBuilder::<sqlx::MySql>::new("SELECT COUNT(*) FROM users");
+        push_user_filters(&mut count_builder, query);
+        let total: i64 = count_builder
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to count users: {}", e))?;
+        let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new("SELECT * FROM users");
+        push_user_filters(&mut query_builder, query);
+        push_user_order(&mut query_builder, query.sort);
+        push_user_page(&mut query_builder, query);
+        let users = query_builder
+            .build_query_as::<crate::domain::dto::User>()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch users: {}", e))?;
+        Ok((users, total))
+    }
+
+    async fn count_rated_users_by_band(&self, contest_type: ContestType, band_width: i32) -> Result<Vec<(i32, i64)>> {
+        let rows = sqlx::query_as::<_, (i64, i64)>(&format!(
+            r#"
+            SELECT CAST((`{rating}` DIV ?) * ? AS SIGNED) AS lower_bound, COUNT(*)
+            FROM users
+            WHERE is_active AND `{rating}` > 0
+            GROUP BY lower_bound
+            ORDER BY lower_bound
+            "#,
+            rating = rating_column(contest_type),
+        ))
+            .bind(band_width)
+            .bind(band_width)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to count users by rating: {}", e))?;
+        Ok(rows
+            .into_iter()
+            .map(|(lower_bound, count)| (lower_bound as i32, count))
+            .collect())
+    }
+
+    async fn get_rating_aggregates(
+        &self,
+        contest_type: ContestType,
+        grouping: UserGrouping,
+    ) -> Result<Vec<RatingAggregate>> {
+        let rows = sqlx::query_as::<_, RatingAggregateRow>(&rating_aggregates_sql(contest_type, grouping))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to aggregate ratings: {}", e))?;
+        Ok(rows
+            .into_iter()
+            .map(RatingAggregate::from)
+            .collect())
+    }
+
     async fn get_user(&self, trap_account_name: &str) -> Result<Option<crate::domain::dto::User>> {
         let user = sqlx::query_as::<_, crate::domain::dto::User>(
             "SELECT * FROM users WHERE trap_account_name = ?"
@@ -37,6 +315,7 @@ impl crate::domain::persist_repository::PersistRepository for PersistRepositoryI
     }
 
     async fn set_users(&self, users: Vec<crate::domain::dto::User>) -> Result<()> {
+        let rows = users.len() as u64;
         let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new(
             r#"
             INSERT INTO users (
@@ -46,7 +325,8 @@ impl crate::domain::persist_repository::PersistRepository for PersistRepositoryI
                 `heuristic_rating`,
                 `is_algo_team`,
                 `is_active`,
-                `grade`
+                `grade`,
+                `last_fetch_error`
             )
             "#
         );
@@ -58,7 +338,8 @@ impl crate::domain::persist_repository::PersistRepository for PersistRepositoryI
                 .push_bind(user.heuristic_rating)
                 .push_bind(user.is_algo_team)
                 .push_bind(user.is_active)
-                .push_bind(user.grade);
+                .push_bind(user.grade)
+                .push_bind(user.last_fetch_error);
         });
         query_builder
             .push(
@@ -69,7 +350,8 @@ impl crate::domain::persist_repository::PersistRepository for PersistRepositoryI
                     `heuristic_rating` = VALUES(`heuristic_rating`),
                     `is_algo_team` = VALUES(`is_algo_team`),
                     `is_active` = VALUES(`is_active`),
-                    `grade` = VALUES(`grade`)
+                    `grade` = VALUES(`grade`),
+                    `last_fetch_error` = VALUES(`last_fetch_error`)
                 "#
             );
         let query = query_builder.build();
@@ -77,6 +359,485 @@ impl crate::domain::persist_repository::PersistRepository for PersistRepositoryI
             .execute(&self.pool)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to execute query: {}", e))?;
+        metrics::counter!("users_written_total").increment(rows);
         Ok(())
     }
+
+    async fn set_contest_results(
+        &self,
+        atcoder_account_name: &str,
+        contest_type: ContestType,
+        results: Vec<ContestResult>,
+    ) -> Result<()> {
+        if results.is_empty() {
+            return Ok(());
+        }
+        let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new(
+            r#"
+            INSERT INTO contest_results (
+                `atcoder_account_name`,
+                `contest_type`,
+                `contest_screen_name`,
+                `contest_name`,
+                `is_rated`,
+                `place`,
+                `old_rating`,
+                `new_rating`,
+                `performance`,
+                `end_time`
+            )
+            "#
+        );
+        query_builder.push_values(results, |mut b, result| {
+            b
+                .push_bind(atcoder_account_name)
+                .push_bind(contest_type.as_str())
+                .push_bind(result.contest_screen_name)
+                .push_bind(result.contest_name)
+                .push_bind(result.is_rated)
+                .push_bind(result.place)
+                .push_bind(result.old_rating)
+                .push_bind(result.new_rating)
+                .push_bind(result.performance)
+                .push_bind(result.end_time);
+        });
+        query_builder
+            .push(
+                r#"
+                ON DUPLICATE KEY UPDATE
+                    `contest_name` = VALUES(`contest_name`),
+                    `is_rated` = VALUES(`is_rated`),
+                    `place` = VALUES(`place`),
+                    `old_rating` = VALUES(`old_rating`),
+                    `new_rating` = VALUES(`new_rating`),
+                    `performance` = VALUES(`performance`),
+                    `end_time` = VALUES(`end_time`)
+                "#
+            );
+        let query = query_builder.build();
+        query
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute query: {}", e))?;
+        Ok(())
+    }
+
+    async fn get_contest_results(
+        &self,
+        atcoder_account_name: &str,
+        contest_type: ContestType,
+    ) -> Result<Vec<ContestResult>> {
+        let rows = sqlx::query_as::<_, ContestResultRow>(
+            r#"
+            SELECT
+                `is_rated`,
+                `place`,
+                `old_rating`,
+                `new_rating`,
+                `performance`,
+                `contest_screen_name`,
+                `contest_name`,
+                `end_time`
+            FROM contest_results
+            WHERE atcoder_account_name = ? AND contest_type = ?
+            ORDER BY end_time ASC
+            "#
+        )
+            .bind(atcoder_account_name)
+            .bind(contest_type.as_str())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch contest results: {}", e))?;
+        Ok(rows.into_iter().map(ContestResult::from).collect())
+    }
+
+    async fn get_rated_contest_counts(
+        &self,
+        atcoder_account_names: &[String],
+    ) -> Result<std::collections::HashMap<(String, ContestType), i64>> {
+        // MySQL rejects an empty IN list
+        if atcoder_account_names.is_empty() {
+            return Ok(Default::default());
+        }
+        let rows = rated_contest_counts_query(atcoder_account_names)
+            .build_query_as::<(String, String, i64)>()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to count rated contests: {}", e))?;
+        rows
+            .into_iter()
+            .map(|(atcoder_account_name, contest_type, count)| {
+                Ok(((atcoder_account_name, contest_type.parse::<ContestType>()?), count))
+            })
+            .collect()
+    }
+
+    async fn get_contest_participations(
+        &self,
+        contest_screen_name: &str,
+    ) -> Result<Vec<ContestParticipation>> {
+        let rows = sqlx::query_as::<_, ContestParticipationRow>(
+            r#"
+            SELECT
+                u.trap_account_name,
+                c.atcoder_account_name,
+                c.contest_type,
+                c.is_rated,
+                c.place,
+                c.old_rating,
+                c.new_rating,
+                c.performance,
+                c.contest_screen_name,
+                c.contest_name,
+                c.end_time
+            FROM contest_results c
+            JOIN users u ON u.atcoder_account_name = c.atcoder_account_name
+            WHERE c.contest_screen_name = ?
+            ORDER BY c.place ASC
+            "#
+        )
+            .bind(contest_screen_name)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch contest participations: {}", e))?;
+        rows.into_iter().map(ContestParticipation::try_from).collect()
+    }
+
+    async fn add_rating_snapshots(
+        &self,
+        taken_at: chrono::DateTime<chrono::Utc>,
+        users: &[crate::domain::dto::User],
+    ) -> Result<()> {
+        if users.is_empty() {
+            return Ok(());
+        }
+        let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new(
+            r#"
+            INSERT INTO rating_snapshots (
+                `trap_account_name`,
+                `taken_at`,
+                `atcoder_rating`,
+                `heuristic_rating`
+            )
+            "#
+        );
+        query_builder.push_values(users, |mut b, user| {
+            b
+                .push_bind(&user.trap_account_name)
+                .push_bind(taken_at)
+                .push_bind(user.atcoder_rating)
+                .push_bind(user.heuristic_rating);
+        });
+        let query = query_builder.build();
+        query
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute query: {}", e))?;
+        Ok(())
+    }
+
+    async fn get_rating_snapshots(
+        &self,
+        trap_account_name: &str,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<crate::domain::dto::RatingSnapshot>> {
+        let snapshots = sqlx::query_as::<_, crate::domain::dto::RatingSnapshot>(
+            r#"
+            SELECT * FROM rating_snapshots
+            WHERE trap_account_name = ? AND taken_at BETWEEN ? AND ?
+            ORDER BY taken_at ASC
+            "#
+        )
+            .bind(trap_account_name)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch rating snapshots: {}", e))?;
+        Ok(snapshots)
+    }
+
+    async fn get_latest_rating_snapshots_before(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<crate::domain::dto::RatingSnapshot>> {
+        let snapshots = sqlx::query_as::<_, crate::domain::dto::RatingSnapshot>(
+            r#"
+            SELECT s.* FROM rating_snapshots s
+            JOIN (
+                SELECT trap_account_name, MAX(taken_at) AS taken_at FROM rating_snapshots
+                WHERE taken_at < ?
+                GROUP BY trap_account_name
+            ) latest
+            ON s.trap_account_name = latest.trap_account_name AND s.taken_at = latest.taken_at
+            "#
+        )
+            .bind(before)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch rating snapshots: {}", e))?;
+        Ok(snapshots)
+    }
+
+    async fn add_update_run(
+        &self,
+        mode: UpdateMode,
+        started_at: chrono::DateTime<chrono::Utc>,
+        finished_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO update_runs (`mode`, `started_at`, `finished_at`) VALUES (?, ?, ?)"
+        )
+            .bind(mode.as_str())
+            .bind(started_at)
+            .bind(finished_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute query: {}", e))?;
+        Ok(())
+    }
+
+    async fn get_last_update_run(&self) -> Result<Option<crate::domain::dto::UpdateRun>> {
+        let run = sqlx::query_as::<_, crate::domain::dto::UpdateRun>(
+            r#"
+            SELECT `id`, `mode`, `started_at`, `finished_at` FROM update_runs
+            ORDER BY started_at DESC
+            LIMIT 1
+            "#
+        )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch last update run: {}", e))?;
+        Ok(run)
+    }
+
+    async fn add_pending_contests(&self, contests: &[FinishedContest]) -> Result<()> {
+        if contests.is_empty() {
+            return Ok(());
+        }
+        let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new(
+            "INSERT IGNORE INTO applied_contests (`contest_id`, `contest_type`, `end_time`) "
+        );
+        query_builder.push_values(contests, |mut b, contest| {
+            b
+                .push_bind(&contest.contest_id)
+                .push_bind(contest.contest_type.as_str())
+                .push_bind(contest.end_time);
+        });
+        query_builder
+            .build()
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute query: {}", e))?;
+        Ok(())
+    }
+
+    async fn get_pending_contests(
+        &self,
+        ended_after: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<FinishedContest>> {
+        let rows = sqlx::query_as::<_, PendingContestRow>(
+            r#"
+            SELECT `contest_id`, `contest_type`, `end_time` FROM applied_contests
+            WHERE applied_at IS NULL AND end_time > ?
+            ORDER BY end_time ASC
+            "#
+        )
+            .bind(ended_after)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch pending contests: {}", e))?;
+        rows.into_iter().map(FinishedContest::try_from).collect()
+    }
+
+    async fn mark_contest_applied(
+        &self,
+        contest_id: &str,
+        applied_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE applied_contests SET `applied_at` = ? WHERE `contest_id` = ?")
+            .bind(applied_at)
+            .bind(contest_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute query: {}", e))?;
+        Ok(())
+    }
+
+    async fn claim_digest(&self, run_id: i64) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE update_runs SET `digest_posted_at` = ? WHERE `id` = ? AND `digest_posted_at` IS NULL"
+        )
+            .bind(chrono::Utc::now())
+            .bind(run_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute query: {}", e))?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn release_digest(&self, run_id: i64) -> Result<()> {
+        sqlx::query("UPDATE update_runs SET `digest_posted_at` = NULL WHERE `id` = ?")
+            .bind(run_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute query: {}", e))?;
+        Ok(())
+    }
+
+    async fn set_contests(&self, contests: Vec<Contest>) -> Result<()> {
+        if contests.is_empty() {
+            return Ok(());
+        }
+        let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new(
+            r#"
+            INSERT INTO contests (
+                `contest_id`,
+                `contest_name`,
+                `contest_url`,
+                `start_time`,
+                `end_time`,
+                `duration_minutes`
+            )
+            "#
+        );
+        query_builder.push_values(contests, |mut b, contest| {
+            let duration_minutes = (contest.end_time - contest.start_time).num_minutes() as i32;
+            b
+                .push_bind(contest.contest_id)
+                .push_bind(contest.contest_name)
+                .push_bind(contest.contest_url)
+                .push_bind(contest.start_time)
+                .push_bind(contest.end_time)
+                .push_bind(duration_minutes);
+        });
+        query_builder.push(
+            r#"
+            ON DUPLICATE KEY UPDATE
+                `contest_name` = VALUES(`contest_name`),
+                `contest_url` = VALUES(`contest_url`),
+                `start_time` = VALUES(`start_time`),
+                `end_time` = VALUES(`end_time`),
+                `duration_minutes` = VALUES(`duration_minutes`)
+            "#
+        );
+        let query = query_builder.build();
+        query
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute query: {}", e))?;
+        Ok(())
+    }
+
+    async fn get_contests_starting_after(
+        &self,
+        after: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<crate::domain::dto::ScheduledContest>> {
+        let contests = sqlx::query_as::<_, crate::domain::dto::ScheduledContest>(
+            r#"
+            SELECT * FROM contests
+            WHERE start_time > ?
+            ORDER BY start_time ASC
+            "#
+        )
+            .bind(after)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch contests: {}", e))?;
+        Ok(contests)
+    }
+
+    async fn claim_contest_reminder(&self, contest_id: &str, offset_minutes: i64) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT IGNORE INTO contest_reminders (`contest_id`, `offset_minutes`, `posted_at`) VALUES (?, ?, ?)"
+        )
+            .bind(contest_id)
+            .bind(offset_minutes)
+            .bind(chrono::Utc::now())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute query: {}", e))?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn release_contest_reminder(&self, contest_id: &str, offset_minutes: i64) -> Result<()> {
+        sqlx::query("DELETE FROM contest_reminders WHERE `contest_id` = ? AND `offset_minutes` = ?")
+            .bind(contest_id)
+            .bind(offset_minutes)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute query: {}", e))?;
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to ping database: {}", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn users_sql(query: &UserQuery) -> String {
+        let mut query_builder = sqlx::QueryBuilder::<sqlx::MySql>::new("SELECT * FROM users");
+        push_user_filters(&mut query_builder, query);
+        push_user_order(&mut query_builder, query.sort);
+        push_user_page(&mut query_builder, query);
+        query_builder.sql().to_string()
+    }
+
+    #[test]
+    fn test_users_sql() {
+        assert_eq!(
+            users_sql(&UserQuery::default()),
+            "SELECT * FROM users WHERE 1 = 1 ORDER BY `trap_account_name` ASC",
+        );
+        let query = UserQuery {
+            grade: Some("23B".to_string()),
+            is_active: Some(true),
+            has_atcoder: Some(true),
+            min_atcoder_rating: Some(1200),
+            sort: Some("atcoderRating:desc".parse().unwrap()),
+            limit: Some(20),
+            offset: 40,
+            ..Default::default()
+        };
+        assert_eq!(
+            users_sql(&query),
+            "SELECT * FROM users WHERE 1 = 1 AND `grade` = ? AND `is_active` = ? \
+            AND `atcoder_account_name` IS NOT NULL AND `atcoder_rating` >= ? \
+            ORDER BY `atcoder_rating` IS NULL, `atcoder_rating` DESC, `trap_account_name` ASC \
+            LIMIT ? OFFSET ?",
+        );
+        let query = UserQuery {
+            offset: 10,
+            ..Default::default()
+        };
+        assert!(users_sql(&query).ends_with(" LIMIT 18446744073709551615 OFFSET ?"));
+    }
+
+    #[test]
+    fn test_rated_contest_counts_sql() {
+        let names = vec!["alice_ac".to_string(), "bob_ac".to_string()];
+        assert_eq!(
+            rated_contest_counts_query(&names).sql(),
+            "SELECT `atcoder_account_name`, `contest_type`, COUNT(*) FROM contest_results \
+            WHERE is_rated AND `atcoder_account_name` IN (?, ?) GROUP BY atcoder_account_name, contest_type",
+        );
+    }
+
+    #[test]
+    fn test_rating_aggregates_sql() {
+        let sql = rating_aggregates_sql(ContestType::Heuristic, UserGrouping::AlgoTeam);
+        assert!(sql.contains("PARTITION BY CASE WHEN `is_algo_team` THEN 'algoTeam' ELSE 'others' END ORDER BY `heuristic_rating`"));
+        assert!(!sql.contains("atcoder_rating"));
+        let sql = rating_aggregates_sql(ContestType::Algorithm, UserGrouping::Grade);
+        assert!(sql.contains("`grade` AS group_key"));
+    }
 }
@@ -0,0 +1,48 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use traq::apis::{configuration, message_api};
+
+use super::client_config::ClientConfig;
+use super::http_client::HttpClient;
+use super::traq_repository::is_retryable;
+
+pub struct TraqBotImpl {
+    conf: configuration::Configuration,
+    http_client: HttpClient,
+}
+
+#[async_trait]
+impl crate::domain::traq_bot::TraqBot for TraqBotImpl {
+    async fn post_message(&self, channel_id: &str, content: &str) -> Result<()> {
+        let request = traq::models::PostMessageRequest {
+            content: content.to_string(),
+            embed: Some(true),
+        };
+        self.http_client
+            .run(
+                || message_api::post_message(&self.conf, channel_id, Some(request.clone())),
+                is_retryable,
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to post message: {}", e))?;
+        Ok(())
+    }
+}
+
+impl TraqBotImpl {
+    /// Shares its configuration with `TraqRepositoryImpl`; both talk to the traQ API as the bot.
+    pub fn new(config: ClientConfig, bot_access_token: String) -> Self {
+        let http_client = HttpClient::new(&config);
+        let conf = configuration::Configuration {
+            base_path: config.base_url.clone(),
+            user_agent: Some(config.user_agent.clone()),
+            client: http_client.inner().clone(),
+            bearer_access_token: Some(bot_access_token),
+            ..Default::default()
+        };
+        Self {
+            conf,
+            http_client,
+        }
+    }
+}
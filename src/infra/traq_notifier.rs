@@ -0,0 +1,222 @@
+use std::sync::Arc;
+use anyhow::Result;
+use async_trait::async_trait;
+use crate::domain::dto::{ContestReport, ScheduledContest};
+use crate::domain::entity::ColorPromotion;
+
+/// The traQ channel of each kind of notification. Kinds without a channel are not posted.
+#[derive(Debug, Clone, Default)]
+pub struct TraqNotifierChannels {
+    pub color_promotions: Option<String>,
+    pub contest_reminders: Option<String>,
+    pub contest_reports: Option<String>,
+}
+
+/// Announces rating and contest events in traQ channels as the bot.
+pub struct TraqNotifierImpl<TB: crate::domain::traq_bot::TraqBot> {
+    traq_bot: Arc<TB>,
+    channels: TraqNotifierChannels,
+}
+
+#[async_trait]
+impl <TB> crate::domain::notifier::Notifier for TraqNotifierImpl<TB>
+where
+    TB: crate::domain::traq_bot::TraqBot,
+{
+    async fn notify_color_promotions(&self, promotions: &[ColorPromotion]) -> Result<()> {
+        if promotions.is_empty() {
+            return Ok(());
+        }
+        let content = format_color_promotions(promotions);
+        self.post(&self.channels.color_promotions, &content)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to post color promotions: {}", e))
+    }
+
+    async fn notify_contest_reminder(
+        &self,
+        contest: &ScheduledContest,
+        starts_in: chrono::Duration,
+    ) -> Result<()> {
+        let content = format_contest_reminder(contest, starts_in);
+        self.post(&self.channels.contest_reminders, &content)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to post contest reminder: {}", e))
+    }
+
+    async fn notify_contest_report(&self, report: &ContestReport) -> Result<()> {
+        let content = format_contest_report(report);
+        self.post(&self.channels.contest_reports, &content)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to post contest report: {}", e))
+    }
+}
+
+impl <TB> TraqNotifierImpl<TB>
+where
+    TB: crate::domain::traq_bot::TraqBot,
+{
+    pub fn new(traq_bot: Arc<TB>, channels: TraqNotifierChannels) -> Self {
+        Self {
+            traq_bot,
+            channels,
+        }
+    }
+
+    async fn post(&self, channel_id: &Option<String>, content: &str) -> Result<()> {
+        match channel_id {
+            Some(channel_id) => self.traq_bot.post_message(channel_id, content).await,
+            None => Ok(()),
+        }
+    }
+}
+
+// Members are mentioned so that they get the congratulations too
+fn format_color_promotions(promotions: &[ColorPromotion]) -> String {
+    let mut s = String::from("### Congratulations on your new colors! :tada:\n");
+    for promotion in promotions {
+        s.push_str(&format!(
+            "- @{} ({}) {}: {} → **{}** ({} → {})",
+            promotion.trap_account_name,
+            promotion.atcoder_account_name,
+            promotion.contest_type.as_str(),
+            promotion.old_color.as_str(),
+            promotion.new_color.as_str(),
+            promotion.old_rating,
+            promotion.new_rating,
+        ));
+        if let Some(contest_name) = &promotion.contest_name {
+            s.push_str(&format!(" in {}", contest_name));
+        }
+        s.push('\n');
+    }
+    s
+}
+
+// Rounded to the nearest minute, since reminders are sent a moment after they fall due,
+// then to the largest unit, e.g. "1 day" or "30 minutes"
+fn format_duration(duration: chrono::Duration) -> String {
+    let minutes = (duration.num_seconds() + 30).div_euclid(60).max(1);
+    let (count, unit) = if minutes >= 24 * 60 && minutes % (24 * 60) == 0 {
+        (minutes / (24 * 60), "day")
+    } else if minutes >= 60 && minutes % 60 == 0 {
+        (minutes / 60, "hour")
+    } else {
+        (minutes, "minute")
+    };
+    format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+}
+
+fn format_contest_reminder(contest: &ScheduledContest, starts_in: chrono::Duration) -> String {
+    let jst = chrono::FixedOffset::east_opt(9 * 3600).expect("JST offset is valid");
+    format!(
+        "### [{}]({}) starts in {}\n- Start: {} JST\n- Duration: {}\n",
+        contest.contest_name,
+        contest.contest_url,
+        format_duration(starts_in),
+        contest.start_time.with_timezone(&jst).format("%Y-%m-%d %H:%M"),
+        format_duration(chrono::Duration::minutes(contest.duration_minutes.into())),
+    )
+}
+
+fn format_contest_report(report: &ContestReport) -> String {
+    let mut s = format!(
+        "### {} results\n{} members took part.\n",
+        report.contest_name,
+        report.participants.len(),
+    );
+    if let Some(best) = &report.best_performer {
+        s.push_str(&format!(
+            "Best performer: {} with a performance of {} :tada:\n",
+            best.trap_account_name,
+            best.performance,
+        ));
+    }
+    s.push_str("\n| Place | traQ | AtCoder | Performance | Rating |\n|---:|---|---|---:|---|\n");
+    for participant in report.participants.iter() {
+        let rating = if participant.is_rated {
+            format!(
+                "{} → {} ({:+})",
+                participant.old_rating,
+                participant.new_rating,
+                participant.rating_delta,
+            )
+        } else {
+            "unrated".to_string()
+        };
+        s.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            participant.place,
+            participant.trap_account_name,
+            participant.atcoder_account_name,
+            participant.performance,
+            rating,
+        ));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entity::ContestType;
+    use crate::domain::notifier::Notifier as _;
+    use crate::domain::rating_color::RatingColor;
+    use crate::testing::recording_traq_bot::RecordingTraqBot;
+
+    #[tokio::test]
+    async fn test_notify_color_promotions() {
+        let traq_bot = Arc::new(RecordingTraqBot::default());
+        let notifier = TraqNotifierImpl::new(traq_bot.clone(), TraqNotifierChannels {
+            color_promotions: Some("channel".to_string()),
+            ..Default::default()
+        });
+        notifier.notify_color_promotions(&[]).await.unwrap();
+        assert!(traq_bot.messages().is_empty());
+
+        notifier
+            .notify_color_promotions(&[ColorPromotion {
+                trap_account_name: "alice".to_string(),
+                atcoder_account_name: "alice_ac".to_string(),
+                contest_type: ContestType::Algorithm,
+                old_rating: 350,
+                new_rating: 412,
+                old_color: RatingColor::Gray,
+                new_color: RatingColor::Brown,
+                contest_name: Some("AtCoder Beginner Contest 400".to_string()),
+            }])
+            .await
+            .unwrap();
+        let messages = traq_bot.messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].0, "channel");
+        assert!(messages[0].1.contains(
+            "- @alice (alice_ac) algorithm: gray → **brown** (350 → 412) in AtCoder Beginner Contest 400\n"
+        ));
+    }
+
+    #[test]
+    fn test_format_contest_reminder() {
+        let start_time = chrono::DateTime::parse_from_rfc3339("2025-04-19T21:00:00+09:00")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let contest = ScheduledContest {
+            contest_id: "abc401".to_string(),
+            contest_name: "AtCoder Beginner Contest 401".to_string(),
+            contest_url: "https://atcoder.jp/contests/abc401".to_string(),
+            start_time,
+            end_time: start_time + chrono::Duration::minutes(100),
+            duration_minutes: 100,
+        };
+        assert_eq!(
+            format_contest_reminder(&contest, chrono::Duration::days(1)),
+            "### [AtCoder Beginner Contest 401](https://atcoder.jp/contests/abc401) starts in 1 day\n\
+            - Start: 2025-04-19 21:00 JST\n\
+            - Duration: 100 minutes\n",
+        );
+        assert_eq!(format_duration(chrono::Duration::minutes(30)), "30 minutes");
+        assert_eq!(format_duration(chrono::Duration::hours(240)), "10 days");
+        assert_eq!(format_duration(chrono::Duration::minutes(120)), "2 hours");
+        assert_eq!(format_duration(chrono::Duration::minutes(30) - chrono::Duration::seconds(2)), "30 minutes");
+    }
+}
@@ -4,21 +4,26 @@ use std::collections::{HashMap, HashSet};
 use traq::apis::{user_api, group_api, configuration};
 use uuid::Uuid;
 
+use super::client_config::ClientConfig;
+use super::http_client::{HttpClient, is_retryable_error, is_retryable_status};
+
+static TRAQ_BASE_URL: &str = "https://q.trap.jp/api/v3";
 static EACH_QUERY_WAIT_TIME_MS: u64 = 200;
 
 pub struct TraqRepositoryImpl {
     conf: configuration::Configuration,
+    http_client: HttpClient,
 }
 
 #[async_trait]
 impl crate::domain::traq_repository::TraqRepository for TraqRepositoryImpl {
     async fn get_members(&self) -> Result<Vec<crate::domain::entity::TrapMember>> {
         tracing::info!("Starting to fetch from traq");
-        let users = user_api::get_users(
-            &self.conf,
-            Some(true),
-            None,
-        )
+        let users = self.http_client
+            .run(
+                || user_api::get_users(&self.conf, Some(true), None),
+                is_retryable,
+            )
             .await
             .map_err(|e| anyhow::anyhow!("Failed to get users: {}", e))?
             .into_iter()
@@ -26,11 +31,10 @@ impl crate::domain::traq_repository::TraqRepository for TraqRepositoryImpl {
                 !user.bot
             })
             .collect::<Vec<_>>();
-        tokio::time::sleep(std::time::Duration::from_millis(EACH_QUERY_WAIT_TIME_MS)).await;
-        let all_groups = group_api::get_user_groups(&self.conf)
+        let all_groups = self.http_client
+            .run(|| group_api::get_user_groups(&self.conf), is_retryable)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to get user groups: {}", e))?;
-        tokio::time::sleep(std::time::Duration::from_millis(EACH_QUERY_WAIT_TIME_MS)).await;
         let algo_team_group_id = all_groups
             .iter()
             .find(|group| {
@@ -73,25 +77,40 @@ impl crate::domain::traq_repository::TraqRepository for TraqRepositoryImpl {
 }
 
 impl TraqRepositoryImpl {
-    pub fn new(conf: configuration::Configuration) -> Self {
-        Self { conf }
+    pub fn new(config: ClientConfig, bot_access_token: String) -> Self {
+        let http_client = HttpClient::new(&config);
+        let conf = configuration::Configuration {
+            base_path: config.base_url.clone(),
+            user_agent: Some(config.user_agent.clone()),
+            client: http_client.inner().clone(),
+            bearer_access_token: Some(bot_access_token),
+            ..Default::default()
+        };
+        Self {
+            conf,
+            http_client,
+        }
+    }
+
+    pub fn default_config() -> ClientConfig {
+        ClientConfig::new("traQ", TRAQ_BASE_URL, std::time::Duration::from_millis(EACH_QUERY_WAIT_TIME_MS))
     }
 
     async fn get_ids_by_group(
         &self,
         group_id: &str,
     ) -> Result<Vec<Uuid>> {
-        let members = group_api::get_user_group_members(
-            &self.conf,
-            group_id,
-        )
+        let members = self.http_client
+            .run(
+                || group_api::get_user_group_members(&self.conf, group_id),
+                is_retryable,
+            )
             .await
             .map_err(|e| anyhow::anyhow!("Failed to get user group members: {}", e))?;
         let ids = members
             .into_iter()
             .map(|member| member.id)
             .collect::<Vec<_>>();
-        tokio::time::sleep(std::time::Duration::from_millis(EACH_QUERY_WAIT_TIME_MS)).await;
         Ok(ids)
     }
 
@@ -100,13 +119,20 @@ impl TraqRepositoryImpl {
     ) -> Vec<traq::models::UserGroup> {
         let reg = regex::Regex::new(r"^[0-9]{2}[BMRD]$")
             .expect("Failed to compile regex");
-        let grade_groups = all_groups
+        all_groups
             .into_iter()
             .filter(|group| {
                 reg.is_match(&group.name)
             })
-            .collect::<Vec<_>>();
-        grade_groups
+            .collect::<Vec<_>>()
+    }
+}
+
+pub(super) fn is_retryable<T>(e: &traq::apis::Error<T>) -> bool {
+    match e {
+        traq::apis::Error::Reqwest(e) => is_retryable_error(e),
+        traq::apis::Error::ResponseError(response) => is_retryable_status(response.status),
+        _ => false,
     }
 }
 
@@ -114,17 +140,14 @@ impl TraqRepositoryImpl {
 mod tests {
     use super::*;
     use crate::domain::traq_repository::TraqRepository as _;
-    use traq::apis::configuration::Configuration;
+    use crate::testing::fake_server::{FakeServer, traq_router};
 
     #[tokio::test]
+    #[ignore = "hits the real traQ API and needs BOT_ACCESS_TOKEN"]
     async fn test_get_members() {
         let access_token = std::env::var("BOT_ACCESS_TOKEN")
             .expect("BOT_ACCESS_TOKEN not set");
-        let configuration = Configuration {
-            bearer_access_token: Some(access_token),
-            ..Default::default()
-        };
-        let traq_repository = TraqRepositoryImpl::new(configuration);
+        let traq_repository = TraqRepositoryImpl::new(TraqRepositoryImpl::default_config(), access_token);
         let result = traq_repository.get_members().await;
         match result {
             Ok(members) => {
@@ -143,4 +166,25 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_get_members_from_fake_server() {
+        let server = FakeServer::start(traq_router()).await;
+        let traq_repository = TraqRepositoryImpl::new(server.config(), "token".to_string());
+        let members = traq_repository.get_members().await.unwrap();
+        let member = |name: &str| {
+            members
+                .iter()
+                .find(|member| member.trap_account_name == name)
+                .unwrap_or_else(|| panic!("{} not found", name))
+        };
+        // Bots are excluded
+        assert_eq!(members.len(), 5);
+        assert!(member("alice").is_algo_team);
+        assert_eq!(member("alice").grade.as_deref(), Some("23B"));
+        assert_eq!(member("bob").grade.as_deref(), Some("24B"));
+        assert!(!member("carol").is_algo_team);
+        assert!(!member("dave").is_active);
+        assert_eq!(member("eve").grade, None);
+    }
+}
@@ -2,24 +2,32 @@ mod domain;
 mod infra;
 mod usecase;
 mod controller;
+#[cfg(test)]
+mod testing;
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use axum::{Router, Extension};
+use axum::Extension;
 
 use infra::{
     traq_repository::TraqRepositoryImpl,
     detail_updater::DetailUpdaterImpl,
     ac_account_updater::TrapMemberAcAccountUpdaterImpl,
+    contest_results_fetcher::ContestResultsFetcherImpl,
+    client_config::ClientConfig,
+    traq_bot::TraqBotImpl,
+    traq_notifier::{TraqNotifierChannels, TraqNotifierImpl},
+    contest_schedule_fetcher::ContestScheduleFetcherImpl,
 };
-use traq::apis::configuration::Configuration;
+use domain::entity::UpdateMode;
+use tokio_util::sync::CancellationToken;
+
+static MAX_DB_CONNECT_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
 
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
         .init();
-    let bot_access_token = std::env::var("TRAQ_BOT_ACCESS_TOKEN")
-        .expect("TRAQ_BOT_ACCESS_TOKEN not set");
+    let metrics_handle = infra::metrics::install_recorder().expect("Failed to set up metrics");
     let mysql_database = std::env::var("NS_MARIADB_DATABASE")
         .expect("NS_MARIADB_DATABASE not set");
     let mysql_database = urlencoding::encode(mysql_database.as_str());
@@ -39,26 +47,100 @@ async fn main() {
         "mysql://{}:{}@{}:{}/{}",
         mysql_user, mysql_password, mysql_host, mysql_port, mysql_database
     );
+    let db_connect_max_retries = std::env::var("DB_CONNECT_MAX_RETRIES")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse::<u32>()
+        .expect("DB_CONNECT_MAX_RETRIES must be a non-negative integer");
+    let db_connect_retry_interval_secs = std::env::var("DB_CONNECT_RETRY_INTERVAL_SECS")
+        .unwrap_or_else(|_| "1".to_string())
+        .parse::<u64>()
+        .expect("DB_CONNECT_RETRY_INTERVAL_SECS must be a non-negative integer");
+    let pool = connect_with_retry(
+        &mysql_url,
+        db_connect_max_retries,
+        std::time::Duration::from_secs(db_connect_retry_interval_secs),
+    )
+        .await
+        .expect("Failed to connect to MySQL");
+    let migrator = infra::migrator::Migrator::new(pool.clone());
+    if std::env::args().nth(1).as_deref() == Some("migrate") {
+        migrator.run().await.expect("Failed to run migrations");
+        return;
+    }
+    let migrate_on_start = std::env::var("MIGRATE_ON_START")
+        .unwrap_or_else(|_| "true".to_string())
+        .parse::<bool>()
+        .expect("MIGRATE_ON_START must be a boolean");
+    if migrate_on_start {
+        migrator.run().await.expect("Failed to run migrations");
+    } else {
+        migrator.check().await.expect("Database schema does not match this binary");
+    }
+    let bot_access_token = std::env::var("TRAQ_BOT_ACCESS_TOKEN")
+        .expect("TRAQ_BOT_ACCESS_TOKEN not set");
+    // The bot event route is only mounted when traQ is set up to send events here
+    let bot_verification_token = std::env::var("TRAQ_BOT_VERIFICATION_TOKEN").ok();
+    let bot_name = std::env::var("TRAQ_BOT_NAME")
+        .unwrap_or_else(|_| "algo-stats".to_string());
+    let digest_channel_id = std::env::var("TRAQ_DIGEST_CHANNEL_ID").ok();
+    let notifier_channels = TraqNotifierChannels {
+        color_promotions: std::env::var("TRAQ_PROMOTION_CHANNEL_ID").ok(),
+        contest_reminders: std::env::var("TRAQ_CONTEST_CHANNEL_ID").ok(),
+        contest_reports: std::env::var("TRAQ_CONTEST_RESULTS_CHANNEL_ID").ok(),
+    };
     let update_on_start = std::env::var("UPDATE_ON_START")
         .unwrap_or_else(|_| "false".to_string())
         .parse::<bool>()
         .expect("UPDATE_ON_START must be a boolean");
-    let conf = Configuration {
-        bearer_access_token: Some(bot_access_token),
-        ..Default::default()
-    };
-    let traq_repository = TraqRepositoryImpl::new(conf);
-    let detail_updater = DetailUpdaterImpl::new();
-    let account_updater = TrapMemberAcAccountUpdaterImpl::new();
-    let pool = sqlx::MySqlPool::connect(&mysql_url)
-        .await
-        .expect("Failed to connect to MySQL");
-    let init_sql = include_str!("../init.sql");
-    tracing::info!("Executing init.sql");
-    sqlx::query(init_sql)
-        .execute(&pool)
-        .await
-        .expect("Failed to execute init.sql");
+    let update_mode = std::env::var("UPDATE_MODE")
+        .unwrap_or_else(|_| "contest".to_string())
+        .parse::<UpdateMode>()
+        .expect("UPDATE_MODE must be either user or contest");
+    let update_cron = std::env::var("UPDATE_CRON")
+        .unwrap_or_else(|_| "0 0 4 * * Mon".to_string());
+    let update_max_retries = std::env::var("UPDATE_MAX_RETRIES")
+        .unwrap_or_else(|_| "3".to_string())
+        .parse::<u32>()
+        .expect("UPDATE_MAX_RETRIES must be a non-negative integer");
+    let update_retry_interval_secs = std::env::var("UPDATE_RETRY_INTERVAL_SECS")
+        .unwrap_or_else(|_| "600".to_string())
+        .parse::<u64>()
+        .expect("UPDATE_RETRY_INTERVAL_SECS must be a non-negative integer");
+    let ready_max_data_age_hours = std::env::var("READY_MAX_DATA_AGE_HOURS")
+        .unwrap_or_else(|_| "192".to_string())
+        .parse::<i64>()
+        .expect("READY_MAX_DATA_AGE_HOURS must be an integer");
+    let contest_schedule_cron = std::env::var("CONTEST_SCHEDULE_CRON")
+        .unwrap_or_else(|_| "0 0 * * * *".to_string());
+    let contest_reminder_cron = std::env::var("CONTEST_REMINDER_CRON")
+        .unwrap_or_else(|_| "0 * * * * *".to_string());
+    let contest_reminder_offsets = std::env::var("CONTEST_REMINDER_OFFSETS_MINUTES")
+        .unwrap_or_else(|_| "1440,30".to_string())
+        .split(',')
+        .map(|offset| {
+            offset
+                .trim()
+                .parse::<i64>()
+                .map(chrono::Duration::minutes)
+                .expect("CONTEST_REMINDER_OFFSETS_MINUTES must be a comma-separated list of integers")
+        })
+        .collect::<Vec<_>>();
+    let traq_config = ClientConfig::from_env("TRAQ", TraqRepositoryImpl::default_config());
+    let atcoder_config = ClientConfig::from_env("ATCODER", DetailUpdaterImpl::default_config());
+    let traportfolio_config = ClientConfig::from_env(
+        "TRAPORTFOLIO",
+        TrapMemberAcAccountUpdaterImpl::default_config(),
+    );
+    let contest_list_config = ClientConfig::from_env(
+        "CONTEST_LIST",
+        ContestResultsFetcherImpl::default_contest_list_config(),
+    );
+    let traq_bot = Arc::new(TraqBotImpl::new(traq_config.clone(), bot_access_token.clone()));
+    let traq_repository = TraqRepositoryImpl::new(traq_config, bot_access_token);
+    let detail_updater = DetailUpdaterImpl::new(atcoder_config.clone());
+    let account_updater = TrapMemberAcAccountUpdaterImpl::new(traportfolio_config);
+    let contest_schedule_fetcher = ContestScheduleFetcherImpl::new(atcoder_config.clone());
+    let contest_fetcher = ContestResultsFetcherImpl::new(atcoder_config, contest_list_config);
     let persist_repository = infra::persist_repository::PersistRepositoryImpl::new(pool);
     let persist_repository = Arc::new(persist_repository);
     let updater = usecase::updater::Updater::new(
@@ -66,45 +148,147 @@ async fn main() {
         account_updater,
         traq_repository,
         persist_repository.clone(),
+        contest_fetcher,
+        update_mode,
     );
-    if update_on_start {
-        tracing::info!("Updating on start");
-        updater.update().await.unwrap();
+    let updater = match digest_channel_id {
+        Some(channel_id) => updater.with_digest(usecase::digest::Digest::new(
+            persist_repository.clone(),
+            traq_bot.clone(),
+            channel_id,
+        )),
+        None => updater,
+    };
+    let updater = updater.with_notifier(TraqNotifierImpl::new(traq_bot.clone(), notifier_channels.clone()));
+    let updater = Arc::new(updater);
+    let contest_schedule = usecase::contest_schedule::ContestSchedule::new(
+        contest_schedule_fetcher,
+        persist_repository.clone(),
+        contest_reminder_offsets,
+    )
+        .with_notifier(TraqNotifierImpl::new(traq_bot.clone(), notifier_channels));
+    let contest_schedule = Arc::new(contest_schedule);
+    let contest_schedule_config = usecase::contest_schedule::ContestScheduleConfig {
+        refresh_cron: contest_schedule_cron,
+        reminder_cron: contest_reminder_cron,
+    };
+    let schedule_config = usecase::updater::ScheduleConfig {
+        cron: update_cron,
+        update_on_start,
+        max_retries: update_max_retries,
+        retry_interval: std::time::Duration::from_secs(update_retry_interval_secs),
+    };
+    let mut app = controller::router::api_router::<infra::persist_repository::PersistRepositoryImpl>();
+    if let Some(verification_token) = bot_verification_token {
+        let bot = usecase::bot::Bot::new(persist_repository.clone(), traq_bot, bot_name);
+        app = app
+            .route(
+                "/bot",
+                axum::routing::post(controller::bot_handler::handler::<infra::persist_repository::PersistRepositoryImpl, TraqBotImpl>),
+            )
+            .layer(Extension(Arc::new(traq_bot_http::RequestParser::new(&verification_token))))
+            .layer(Extension(Arc::new(bot)));
+    } else {
+        tracing::info!("TRAQ_BOT_VERIFICATION_TOKEN not set, not accepting bot events");
     }
-    let app = Router::new()
-        .route("/users", axum::routing::get(controller::get_users_handler::handler::<infra::persist_repository::PersistRepositoryImpl>))
-        .route(
-            "/rate/heuristic/{trap_account_name}",
-            axum::routing::get(controller::get_rate_handler::heur_handler::<infra::persist_repository::PersistRepositoryImpl>),
-        )
+    let app = app
+        .route_layer(axum::middleware::from_fn(controller::metrics_handler::track_requests))
+        .route("/metrics", axum::routing::get(controller::metrics_handler::handler))
+        .route("/healthz", axum::routing::get(controller::health_handler::healthz_handler))
         .route(
-            "/rate/algorithm/{trap_account_name}",
-            axum::routing::get(controller::get_rate_handler::algo_handler::<infra::persist_repository::PersistRepositoryImpl>),
+            "/readyz",
+            axum::routing::get(controller::health_handler::readyz_handler::<infra::persist_repository::PersistRepositoryImpl>),
         )
+        .layer(Extension(metrics_handle))
+        .layer(Extension(controller::health_handler::ReadinessConfig {
+            max_data_age: chrono::Duration::hours(ready_max_data_age_hours),
+        }))
         .layer(Extension(persist_repository));
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000")
         .await
         .expect("Failed to bind to address");
     tracing::info!("Listening on {}", listener.local_addr().unwrap());
-    axum::serve(listener, app.clone())
-        .await
-        .expect("Failed to start server");
-    /*
-    let either = futures::future::select(
-        Box::pin(async move {
-            updater.serve().await.unwrap();
-        }),
-        Box::pin(async move {
-            axum::serve(listener, app).await.unwrap();
-        }),
-    ).await;
-    match either {
-        futures::future::Either::Left(_) => {
-            println!("Updater finished");
-        }
-        futures::future::Either::Right(_) => {
-            println!("Server finished");
+    let shutdown = CancellationToken::new();
+    tokio::spawn(wait_for_shutdown_signal(shutdown.clone()));
+    let server = axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown.clone().cancelled_owned());
+    let (server_result, updater_result, contest_schedule_result) = tokio::join!(
+        async {
+            let result = server.await;
+            // Stop the schedulers too if the server exits on its own
+            shutdown.cancel();
+            result
+        },
+        async {
+            let result = updater.serve(schedule_config, shutdown.clone()).await;
+            shut_down_on_error("Update scheduler", &result, &shutdown);
+            result
+        },
+        async {
+            let result = contest_schedule.serve(contest_schedule_config, shutdown.clone()).await;
+            shut_down_on_error("Contest schedule", &result, &shutdown);
+            result
+        },
+    );
+    server_result.expect("Failed to start server");
+    updater_result.expect("Failed to run scheduler");
+    contest_schedule_result.expect("Failed to run contest schedule");
+    tracing::info!("Shut down gracefully");
+}
+
+/// Stops the server when a scheduler fails, e.g. on an invalid cron expression,
+/// rather than serving without scheduled jobs.
+fn shut_down_on_error(name: &str, result: &anyhow::Result<()>, shutdown: &CancellationToken) {
+    if let Err(e) = result {
+        tracing::error!("{} failed, shutting down: {}", name, e);
+        shutdown.cancel();
+    }
+}
+
+/// Connects to MySQL, retrying with exponential backoff while it is not up yet.
+async fn connect_with_retry(
+    url: &str,
+    max_retries: u32,
+    retry_interval: std::time::Duration,
+) -> Result<sqlx::MySqlPool, sqlx::Error> {
+    let mut interval = retry_interval;
+    let mut attempt = 0;
+    loop {
+        match sqlx::MySqlPool::connect(url).await {
+            Ok(pool) => return Ok(pool),
+            Err(e) if attempt < max_retries => {
+                tracing::warn!(
+                    "Failed to connect to MySQL (attempt {}/{}), retrying in {:?}: {}",
+                    attempt + 1,
+                    max_retries + 1,
+                    interval,
+                    e
+                );
+                tokio::time::sleep(interval).await;
+                interval = (interval * 2).min(MAX_DB_CONNECT_RETRY_INTERVAL);
+            }
+            Err(e) => return Err(e),
         }
+        attempt += 1;
+    }
+}
+
+async fn wait_for_shutdown_signal(shutdown: CancellationToken) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to listen for Ctrl+C");
+    };
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
     }
-*/
+    tracing::info!("Received shutdown signal");
+    shutdown.cancel();
 }
@@ -0,0 +1,3 @@
+pub mod fake_server;
+pub mod in_memory_persist_repository;
+pub mod recording_traq_bot;
@@ -0,0 +1,194 @@
+use std::io::Write;
+use std::time::Duration;
+use axum::{
+    Json, Router,
+    extract::{Path, Query},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use flate2::{Compression, write::GzEncoder};
+use crate::infra::client_config::ClientConfig;
+
+/// An HTTP server bound to a random local port, stopped when dropped.
+pub struct FakeServer {
+    pub base_url: String,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl FakeServer {
+    pub async fn start(router: Router) -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind fake server");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+        FakeServer { base_url, handle }
+    }
+
+    /// A client config pointing at this server without any politeness or backoff delay.
+    pub fn config(&self) -> ClientConfig {
+        ClientConfig {
+            retry_base_delay: Duration::from_millis(1),
+            ..ClientConfig::new("fake", &self.base_url, Duration::ZERO)
+        }
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Fakes of every upstream the updater talks to.
+pub struct FakeUpstreams {
+    pub atcoder: FakeServer,
+    pub contest_list: FakeServer,
+    pub traportfolio: FakeServer,
+    pub traq: FakeServer,
+}
+
+impl FakeUpstreams {
+    /// `contests` is served as the contest list, see [`contest_list_entry`].
+    pub async fn start(contests: Vec<serde_json::Value>) -> Self {
+        FakeUpstreams {
+            atcoder: FakeServer::start(atcoder_router()).await,
+            contest_list: FakeServer::start(contest_list_router(contests)).await,
+            traportfolio: FakeServer::start(traportfolio_router()).await,
+            traq: FakeServer::start(traq_router()).await,
+        }
+    }
+}
+
+fn gzip_json(body: &str) -> Response {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body.as_bytes()).unwrap();
+    let bytes = encoder.finish().unwrap();
+    (
+        [
+            (header::CONTENT_TYPE, "application/json"),
+            (header::CONTENT_ENCODING, "gzip"),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
+fn plain_json(body: &str) -> Response {
+    ([(header::CONTENT_TYPE, "application/json")], body.to_string()).into_response()
+}
+
+#[derive(serde::Deserialize)]
+struct HistoryQuery {
+    #[serde(rename = "contestType")]
+    contest_type: Option<String>,
+}
+
+/// Serves `alice_ac` and `bob_ac`; every other user is a 404 like a renamed account.
+/// The contest list has abc401, arc196, ahc046 and agc072 coming up in April 2025, and the
+/// archive lists abc300, abc400, ahc045 and the non-AHC heuristic contest asprocon11.
+pub fn atcoder_router() -> Router {
+    Router::new()
+        .route(
+            "/users/{username}/history/json",
+            get(|Path(username): Path<String>, Query(query): Query<HistoryQuery>| async move {
+                let is_heur = query.contest_type.as_deref() == Some("heuristic");
+                let body = match (username.as_str(), is_heur) {
+                    ("alice_ac", false) => include_str!("fixtures/atcoder/history_alice_ac.json"),
+                    ("alice_ac", true) => include_str!("fixtures/atcoder/heuristic_history_alice_ac.json"),
+                    ("bob_ac", false) => include_str!("fixtures/atcoder/history_bob_ac.json"),
+                    ("bob_ac", true) => include_str!("fixtures/atcoder/heuristic_history_bob_ac.json"),
+                    _ => return StatusCode::NOT_FOUND.into_response(),
+                };
+                gzip_json(body)
+            }),
+        )
+        .route(
+            "/contests/{contest_id}/results/json",
+            get(|Path(contest_id): Path<String>| async move {
+                match contest_id.as_str() {
+                    "abc400" => gzip_json(include_str!("fixtures/atcoder/results_abc400.json")),
+                    _ => StatusCode::NOT_FOUND.into_response(),
+                }
+            }),
+        )
+        .route(
+            "/contests/",
+            get(|| async { axum::response::Html(include_str!("fixtures/atcoder/contests.html")) }),
+        )
+        .route(
+            "/contests/archive",
+            get(|| async { axum::response::Html(include_str!("fixtures/atcoder/archive.html")) }),
+        )
+}
+
+/// A contest list entry in the format of `/resources/contests.json`.
+pub fn contest_list_entry(
+    id: &str,
+    end_time: chrono::DateTime<chrono::Utc>,
+    rate_change: &str,
+) -> serde_json::Value {
+    let duration_second = 6000;
+    serde_json::json!({
+        "id": id,
+        "start_epoch_second": end_time.timestamp() - duration_second,
+        "duration_second": duration_second,
+        "title": id.to_uppercase(),
+        "rate_change": rate_change,
+    })
+}
+
+pub fn contest_list_router(contests: Vec<serde_json::Value>) -> Router {
+    Router::new().route(
+        "/resources/contests.json",
+        get(move || async move { Json(contests) }),
+    )
+}
+
+/// Serves alice, bob, carol and dave; eve is listed but fetching the detail fails with a 500.
+pub fn traportfolio_router() -> Router {
+    Router::new()
+        .route(
+            "/users",
+            get(|| async { gzip_json(include_str!("fixtures/traportfolio/users.json")) }),
+        )
+        .route(
+            "/users/{id}",
+            get(|Path(id): Path<String>| async move {
+                let body = match id.as_str() {
+                    "22222222-0000-0000-0000-000000000001" => include_str!("fixtures/traportfolio/user_alice.json"),
+                    "22222222-0000-0000-0000-000000000002" => include_str!("fixtures/traportfolio/user_bob.json"),
+                    "22222222-0000-0000-0000-000000000003" => include_str!("fixtures/traportfolio/user_carol.json"),
+                    "22222222-0000-0000-0000-000000000004" => include_str!("fixtures/traportfolio/user_dave.json"),
+                    _ => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+                };
+                plain_json(body)
+            }),
+        )
+}
+
+pub fn traq_router() -> Router {
+    Router::new()
+        .route(
+            "/users",
+            get(|| async { plain_json(include_str!("fixtures/traq/users.json")) }),
+        )
+        .route(
+            "/groups",
+            get(|| async { plain_json(include_str!("fixtures/traq/groups.json")) }),
+        )
+        .route(
+            "/groups/{group_id}/members",
+            get(|Path(group_id): Path<String>| async move {
+                let groups: Vec<serde_json::Value> =
+                    serde_json::from_str(include_str!("fixtures/traq/groups.json")).unwrap();
+                match groups.into_iter().find(|group| group["id"] == group_id.as_str()) {
+                    Some(group) => Json(group["members"].clone()).into_response(),
+                    None => StatusCode::NOT_FOUND.into_response(),
+                }
+            }),
+        )
+}
@@ -0,0 +1,416 @@
+use std::sync::Mutex;
+use anyhow::Result;
+use async_trait::async_trait;
+use crate::domain::dto::{RatingSnapshot, ScheduledContest, UpdateRun, User};
+use crate::domain::entity::{
+    Contest, ContestParticipation, ContestResult, ContestType, FinishedContest, RatingAggregate, UpdateMode, UserGrouping,
+    UserQuery, UserSortKey,
+};
+
+#[derive(Default)]
+pub struct State {
+    pub users: Vec<User>,
+    pub contest_results: Vec<(String, ContestType, ContestResult)>,
+    pub rating_snapshots: Vec<RatingSnapshot>,
+    pub update_runs: Vec<UpdateRun>,
+    /// Finished contests and when their results were applied.
+    pub applied_contests: Vec<(FinishedContest, Option<chrono::DateTime<chrono::Utc>>)>,
+    pub digest_posted_runs: Vec<i64>,
+    pub contests: Vec<Contest>,
+    pub contest_reminders: Vec<(String, i64)>,
+    /// Makes `ping` fail, as if the database were down.
+    pub is_unreachable: bool,
+}
+
+/// A `PersistRepository` backed by plain vectors, for tests.
+#[derive(Default)]
+pub struct InMemoryPersistRepository {
+    pub state: Mutex<State>,
+}
+
+#[async_trait]
+impl crate::domain::persist_repository::PersistRepository for InMemoryPersistRepository {
+    async fn get_users(&self) -> Result<Vec<User>> {
+        Ok(self.state.lock().unwrap().users.clone())
+    }
+
+    async fn set_users(&self, users: Vec<User>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        for user in users {
+            state.users.retain(|u| u.trap_account_name != user.trap_account_name);
+            state.users.push(user);
+        }
+        Ok(())
+    }
+
+    async fn find_users(&self, query: &UserQuery) -> Result<(Vec<User>, i64)> {
+        let mut users = self.state
+            .lock()
+            .unwrap()
+            .users
+            .iter()
+            .filter(|u| query.grade.as_ref().is_none_or(|grade| u.grade.as_ref() == Some(grade)))
+            .filter(|u| query.is_algo_team.is_none_or(|v| u.is_algo_team == Some(v)))
+            .filter(|u| query.is_active.is_none_or(|v| u.is_active == Some(v)))
+            .filter(|u| query.has_atcoder.is_none_or(|v| u.atcoder_account_name.is_some() == v))
+            .filter(|u| query.min_atcoder_rating.is_none_or(|min| u.atcoder_rating.is_some_and(|r| r >= min)))
+            .filter(|u| query.min_heuristic_rating.is_none_or(|min| u.heuristic_rating.is_some_and(|r| r >= min)))
+            .cloned()
+            .collect::<Vec<_>>();
+        users.sort_by(|a, b| a.trap_account_name.cmp(&b.trap_account_name));
+        if let Some(sort) = query.sort {
+            users.sort_by(|a, b| match sort.key {
+                UserSortKey::TrapAccountName => {
+                    nulls_last(Some(&a.trap_account_name), Some(&b.trap_account_name), sort.descending)
+                }
+                UserSortKey::AtcoderRating => nulls_last(a.atcoder_rating, b.atcoder_rating, sort.descending),
+                UserSortKey::HeuristicRating => nulls_last(a.heuristic_rating, b.heuristic_rating, sort.descending),
+                UserSortKey::Grade => nulls_last(a.grade.as_ref(), b.grade.as_ref(), sort.descending),
+            });
+        }
+        let total = users.len() as i64;
+        let users = users
+            .into_iter()
+            .skip(query.offset as usize)
+            .take(query.limit.map_or(usize::MAX, |limit| limit as usize))
+            .collect();
+        Ok((users, total))
+    }
+
+    async fn count_rated_users_by_band(&self, contest_type: ContestType, band_width: i32) -> Result<Vec<(i32, i64)>> {
+        let mut counts = std::collections::BTreeMap::new();
+        for user in self.state.lock().unwrap().users.iter() {
+            if let Some(rating) = user.rating(contest_type)
+                && user.is_active == Some(true)
+                && rating > 0
+            {
+                *counts.entry(rating / band_width * band_width).or_insert(0) += 1;
+            }
+        }
+        Ok(counts.into_iter().collect())
+    }
+
+    async fn get_rating_aggregates(
+        &self,
+        contest_type: ContestType,
+        grouping: UserGrouping,
+    ) -> Result<Vec<RatingAggregate>> {
+        let mut groups = std::collections::BTreeMap::<Option<String>, Vec<User>>::new();
+        for user in self.state.lock().unwrap().users.iter() {
+            if user.is_active != Some(true) {
+                continue;
+            }
+            let key = match grouping {
+                UserGrouping::All => None,
+                UserGrouping::Grade => user.grade.clone(),
+                UserGrouping::AlgoTeam if user.is_algo_team == Some(true) => Some("algoTeam".to_string()),
+                UserGrouping::AlgoTeam => Some("others".to_string()),
+            };
+            groups.entry(key).or_default().push(user.clone());
+        }
+        Ok(groups
+            .into_iter()
+            .map(|(group, users)| {
+                let mut ratings = users
+                    .iter()
+                    .filter_map(|u| u.rating(contest_type))
+                    .filter(|rating| *rating > 0)
+                    .collect::<Vec<_>>();
+                ratings.sort();
+                let n = ratings.len();
+                RatingAggregate {
+                    group,
+                    members: users.len() as i64,
+                    linked: users.iter().filter(|u| u.atcoder_account_name.is_some()).count() as i64,
+                    rated: n as i64,
+                    mean_rating: (n > 0).then(|| ratings.iter().sum::<i32>() as f64 / n as f64),
+                    median_rating: (n > 0).then(|| (ratings[(n - 1) / 2] + ratings[n / 2]) as f64 / 2.0),
+                    max_rating: ratings.last().copied(),
+                }
+            })
+            .collect())
+    }
+
+    async fn get_user(&self, trap_account_name: &str) -> Result<Option<User>> {
+        Ok(self.state
+            .lock()
+            .unwrap()
+            .users
+            .iter()
+            .find(|u| u.trap_account_name == trap_account_name)
+            .cloned())
+    }
+
+    async fn set_contest_results(
+        &self,
+        atcoder_account_name: &str,
+        contest_type: ContestType,
+        results: Vec<ContestResult>,
+    ) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        for result in results {
+            state.contest_results.retain(|(name, t, r)| {
+                !(name == atcoder_account_name
+                    && *t == contest_type
+                    && r.contest_screen_name == result.contest_screen_name)
+            });
+            state.contest_results.push((atcoder_account_name.to_string(), contest_type, result));
+        }
+        Ok(())
+    }
+
+    async fn get_contest_results(
+        &self,
+        atcoder_account_name: &str,
+        contest_type: ContestType,
+    ) -> Result<Vec<ContestResult>> {
+        let mut results = self.state
+            .lock()
+            .unwrap()
+            .contest_results
+            .iter()
+            .filter(|(name, t, _)| name == atcoder_account_name && *t == contest_type)
+            .map(|(_, _, r)| r.clone())
+            .collect::<Vec<_>>();
+        results.sort_by_key(|r| r.end_time);
+        Ok(results)
+    }
+
+    async fn get_rated_contest_counts(
+        &self,
+        atcoder_account_names: &[String],
+    ) -> Result<std::collections::HashMap<(String, ContestType), i64>> {
+        let mut counts = std::collections::HashMap::new();
+        for (name, contest_type, result) in self.state.lock().unwrap().contest_results.iter() {
+            if result.is_rated && atcoder_account_names.contains(name) {
+                *counts.entry((name.clone(), *contest_type)).or_insert(0) += 1;
+            }
+        }
+        Ok(counts)
+    }
+
+    async fn get_contest_participations(
+        &self,
+        contest_screen_name: &str,
+    ) -> Result<Vec<ContestParticipation>> {
+        let state = self.state.lock().unwrap();
+        let mut participations = state.contest_results
+            .iter()
+            .filter(|(_, _, r)| r.contest_screen_name == contest_screen_name)
+            .filter_map(|(name, t, r)| {
+                let user = state.users
+                    .iter()
+                    .find(|u| u.atcoder_account_name.as_ref().is_some_and(|n| n.eq_ignore_ascii_case(name)))?;
+                Some(ContestParticipation {
+                    trap_account_name: user.trap_account_name.clone(),
+                    atcoder_account_name: name.clone(),
+                    contest_type: *t,
+                    result: r.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        participations.sort_by_key(|p| p.result.place);
+        Ok(participations)
+    }
+
+    async fn add_rating_snapshots(
+        &self,
+        taken_at: chrono::DateTime<chrono::Utc>,
+        users: &[User],
+    ) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        for user in users {
+            state.rating_snapshots.push(RatingSnapshot {
+                trap_account_name: user.trap_account_name.clone(),
+                taken_at,
+                atcoder_rating: user.atcoder_rating,
+                heuristic_rating: user.heuristic_rating,
+            });
+        }
+        Ok(())
+    }
+
+    async fn get_rating_snapshots(
+        &self,
+        trap_account_name: &str,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<RatingSnapshot>> {
+        let mut snapshots = self.state
+            .lock()
+            .unwrap()
+            .rating_snapshots
+            .iter()
+            .filter(|s| s.trap_account_name == trap_account_name && from <= s.taken_at && s.taken_at <= to)
+            .cloned()
+            .collect::<Vec<_>>();
+        snapshots.sort_by_key(|s| s.taken_at);
+        Ok(snapshots)
+    }
+
+    async fn get_latest_rating_snapshots_before(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<RatingSnapshot>> {
+        let mut latest = std::collections::HashMap::<String, RatingSnapshot>::new();
+        for snapshot in self.state.lock().unwrap().rating_snapshots.iter() {
+            if snapshot.taken_at >= before {
+                continue;
+            }
+            let is_newer = latest
+                .get(&snapshot.trap_account_name)
+                .is_none_or(|s| s.taken_at < snapshot.taken_at);
+            if is_newer {
+                latest.insert(snapshot.trap_account_name.clone(), snapshot.clone());
+            }
+        }
+        Ok(latest.into_values().collect())
+    }
+
+    async fn add_update_run(
+        &self,
+        mode: UpdateMode,
+        started_at: chrono::DateTime<chrono::Utc>,
+        finished_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let id = state.update_runs.len() as i64 + 1;
+        state.update_runs.push(UpdateRun {
+            id,
+            mode: mode.as_str().to_string(),
+            started_at,
+            finished_at,
+        });
+        Ok(())
+    }
+
+    async fn get_last_update_run(&self) -> Result<Option<UpdateRun>> {
+        Ok(self.state
+            .lock()
+            .unwrap()
+            .update_runs
+            .iter()
+            .max_by_key(|run| run.started_at)
+            .cloned())
+    }
+
+    async fn add_pending_contests(&self, contests: &[FinishedContest]) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        for contest in contests {
+            if !state.applied_contests.iter().any(|(c, _)| c.contest_id == contest.contest_id) {
+                state.applied_contests.push((contest.clone(), None));
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_pending_contests(
+        &self,
+        ended_after: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<FinishedContest>> {
+        let mut contests = self.state
+            .lock()
+            .unwrap()
+            .applied_contests
+            .iter()
+            .filter(|(contest, applied_at)| applied_at.is_none() && contest.end_time > ended_after)
+            .map(|(contest, _)| contest.clone())
+            .collect::<Vec<_>>();
+        contests.sort_by_key(|contest| contest.end_time);
+        Ok(contests)
+    }
+
+    async fn mark_contest_applied(
+        &self,
+        contest_id: &str,
+        applied_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        for (contest, at) in state.applied_contests.iter_mut() {
+            if contest.contest_id == contest_id {
+                *at = Some(applied_at);
+            }
+        }
+        Ok(())
+    }
+
+    async fn claim_digest(&self, run_id: i64) -> Result<bool> {
+        let mut state = self.state.lock().unwrap();
+        if state.digest_posted_runs.contains(&run_id) {
+            return Ok(false);
+        }
+        state.digest_posted_runs.push(run_id);
+        Ok(true)
+    }
+
+    async fn release_digest(&self, run_id: i64) -> Result<()> {
+        self.state.lock().unwrap().digest_posted_runs.retain(|id| *id != run_id);
+        Ok(())
+    }
+
+    async fn set_contests(&self, contests: Vec<Contest>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        for contest in contests {
+            state.contests.retain(|c| c.contest_id != contest.contest_id);
+            state.contests.push(contest);
+        }
+        Ok(())
+    }
+
+    async fn get_contests_starting_after(
+        &self,
+        after: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<ScheduledContest>> {
+        let mut contests = self.state
+            .lock()
+            .unwrap()
+            .contests
+            .iter()
+            .filter(|c| c.start_time > after)
+            .map(|c| ScheduledContest {
+                contest_id: c.contest_id.clone(),
+                contest_name: c.contest_name.clone(),
+                contest_url: c.contest_url.clone(),
+                start_time: c.start_time,
+                end_time: c.end_time,
+                duration_minutes: (c.end_time - c.start_time).num_minutes() as i32,
+            })
+            .collect::<Vec<_>>();
+        contests.sort_by_key(|c| c.start_time);
+        Ok(contests)
+    }
+
+    async fn claim_contest_reminder(&self, contest_id: &str, offset_minutes: i64) -> Result<bool> {
+        let mut state = self.state.lock().unwrap();
+        let key = (contest_id.to_string(), offset_minutes);
+        if state.contest_reminders.contains(&key) {
+            return Ok(false);
+        }
+        state.contest_reminders.push(key);
+        Ok(true)
+    }
+
+    async fn release_contest_reminder(&self, contest_id: &str, offset_minutes: i64) -> Result<()> {
+        self.state
+            .lock()
+            .unwrap()
+            .contest_reminders
+            .retain(|(id, offset)| !(id == contest_id && *offset == offset_minutes));
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<()> {
+        if self.state.lock().unwrap().is_unreachable {
+            return Err(anyhow::anyhow!("Database is unreachable"));
+        }
+        Ok(())
+    }
+}
+
+fn nulls_last<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> std::cmp::Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if descending => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (a, b) => a.is_none().cmp(&b.is_none()),
+    }
+}
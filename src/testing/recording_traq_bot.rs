@@ -0,0 +1,27 @@
+use std::sync::Mutex;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// A `TraqBot` that keeps every posted message instead of sending it.
+#[derive(Default)]
+pub struct RecordingTraqBot {
+    messages: Mutex<Vec<(String, String)>>,
+}
+
+impl RecordingTraqBot {
+    /// Posted messages as `(channel_id, content)`, oldest first.
+    pub fn messages(&self) -> Vec<(String, String)> {
+        self.messages.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl crate::domain::traq_bot::TraqBot for RecordingTraqBot {
+    async fn post_message(&self, channel_id: &str, content: &str) -> Result<()> {
+        self.messages
+            .lock()
+            .unwrap()
+            .push((channel_id.to_string(), content.to_string()));
+        Ok(())
+    }
+}
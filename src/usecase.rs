@@ -1 +1,12 @@
 pub mod updater;
+pub mod ranking;
+pub mod bot;
+pub mod digest;
+pub mod contest_schedule;
+pub mod contest_report;
+pub mod stats;
+pub mod badge;
+pub mod svg;
+pub mod rating_graph;
+pub mod compare;
+pub mod health;
\ No newline at end of file
@@ -0,0 +1,144 @@
+use crate::domain::entity::ContestType;
+use crate::domain::rating_color::RatingColor;
+use super::svg::escape_xml;
+
+// Unrated accounts and the label side of every badge
+const LABEL_COLOR: &str = "#555";
+const UNRATED_COLOR: &str = "#9f9f9f";
+const HORIZONTAL_PADDING: f64 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BadgeStyle {
+    /// Rounded corners with a subtle gradient, as shields.io's default.
+    #[default]
+    Flat,
+    /// Square corners without a gradient.
+    FlatSquare,
+}
+
+impl std::str::FromStr for BadgeStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flat" => Ok(BadgeStyle::Flat),
+            "flat-square" => Ok(BadgeStyle::FlatSquare),
+            _ => Err(anyhow::anyhow!("Unknown badge style: {}", s)),
+        }
+    }
+}
+
+pub fn default_label(contest_type: ContestType) -> &'static str {
+    match contest_type {
+        ContestType::Algorithm => "AtCoder",
+        ContestType::Heuristic => "AtCoder Heuristic",
+    }
+}
+
+/// Approximates the width of `text` in 11px Verdana, the font shields.io measures with.
+fn text_width(text: &str) -> f64 {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '\'' | '|' | '!' => 3.5,
+            ' ' | 'f' | 't' | 'r' | '(' | ')' | '[' | ']' | '-' => 4.5,
+            'm' | 'w' | 'M' | 'W' => 10.0,
+            c if c.is_ascii_uppercase() => 7.5,
+            c if c.is_ascii() => 6.5,
+            // Mostly CJK, which is about square
+            _ => 11.0,
+        })
+        .sum()
+}
+
+/// Renders a badge reading `label | rating` in the rating's color. A rating of 0 reads "unrated".
+pub fn render_rating_badge(label: &str, rating: Option<i32>, style: BadgeStyle) -> String {
+    let (message, color) = match rating {
+        Some(rating) if rating > 0 => (rating.to_string(), RatingColor::from_rating(rating).hex()),
+        _ => ("unrated".to_string(), UNRATED_COLOR),
+    };
+    render_badge(label, &message, color, style)
+}
+
+fn render_badge(label: &str, message: &str, color: &str, style: BadgeStyle) -> String {
+    let label_width = (text_width(label) + 2.0 * HORIZONTAL_PADDING).round();
+    let message_width = (text_width(message) + 2.0 * HORIZONTAL_PADDING).round();
+    let width = label_width + message_width;
+    let label = escape_xml(label);
+    let message = escape_xml(message);
+    let (radius, gradient) = match style {
+        BadgeStyle::Flat => (
+            3,
+            concat!(
+                r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/>"##,
+                r##"<stop offset="1" stop-opacity=".1"/></linearGradient>"##,
+            ),
+        ),
+        BadgeStyle::FlatSquare => (0, ""),
+    };
+    let gradient_rect = if gradient.is_empty() {
+        String::new()
+    } else {
+        format!(r##"<rect width="{}" height="20" fill="url(#s)"/>"##, width)
+    };
+    format!(
+        concat!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}">"##,
+            r##"<title>{label}: {message}</title>"##,
+            r##"{gradient}"##,
+            r##"<clipPath id="r"><rect width="{width}" height="20" rx="{radius}" fill="#fff"/></clipPath>"##,
+            r##"<g clip-path="url(#r)">"##,
+            r##"<rect width="{label_width}" height="20" fill="{label_color}"/>"##,
+            r##"<rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>"##,
+            r##"{gradient_rect}"##,
+            r##"</g>"##,
+            r##"<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">"##,
+            r##"<text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text>"##,
+            r##"<text x="{label_x}" y="14">{label}</text>"##,
+            r##"<text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text>"##,
+            r##"<text x="{message_x}" y="14">{message}</text>"##,
+            r##"</g></svg>"##,
+        ),
+        width = width,
+        label = label,
+        message = message,
+        gradient = gradient,
+        radius = radius,
+        label_width = label_width,
+        message_width = message_width,
+        label_color = LABEL_COLOR,
+        color = color,
+        gradient_rect = gradient_rect,
+        label_x = label_width / 2.0,
+        message_x = label_width + message_width / 2.0,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_rating_badge() {
+        let badge = render_rating_badge("AtCoder", Some(1866), BadgeStyle::Flat);
+        assert!(badge.starts_with("<svg "));
+        assert!(badge.contains(r##"fill="#0000FF""##));
+        assert!(badge.contains("<title>AtCoder: 1866</title>"));
+        assert!(badge.contains(r#"rx="3""#));
+
+        let badge = render_rating_badge("<b>", Some(0), BadgeStyle::FlatSquare);
+        assert!(badge.contains("<title>&lt;b&gt;: unrated</title>"));
+        assert!(badge.contains(r##"fill="#9f9f9f""##));
+        assert!(!badge.contains("linearGradient"));
+        // Longer labels make wider badges
+        let width = |badge: &str| {
+            badge["<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"".len()..]
+                .split('"')
+                .next()
+                .unwrap()
+                .parse::<f64>()
+                .unwrap()
+        };
+        assert!(width(&render_rating_badge("AtCoder Heuristic", Some(1866), BadgeStyle::Flat))
+            > width(&render_rating_badge("AtCoder", Some(1866), BadgeStyle::Flat)));
+    }
+}
@@ -0,0 +1,191 @@
+use std::sync::Arc;
+use anyhow::Result;
+use crate::domain::entity::ContestType;
+use super::ranking::{format_ranking, top_users};
+
+const RANKING_SIZE: usize = 10;
+static HELP: &str = "\
+Usage:
+- `@algo-stats rate <traQ ID>`: ratings of a member
+- `@algo-stats ranking`: top rated members";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Rate(String),
+    Ranking,
+    Help,
+}
+
+/// Parses a message such as `@algo-stats rate alice`.
+///
+/// Messages that do not start with a mention of the bot are ignored unless
+/// `mention_required` is false, as in direct messages.
+pub fn parse_command(text: &str, bot_name: &str, mention_required: bool) -> Option<Command> {
+    let mut words = text.split_whitespace().peekable();
+    let mentioned = words
+        .peek()
+        .and_then(|word| word.strip_prefix('@'))
+        .is_some_and(|name| is_bot_name(name, bot_name));
+    if mentioned {
+        words.next();
+    } else if mention_required {
+        return None;
+    }
+    let command = match words.next().map(|word| word.to_ascii_lowercase()).as_deref() {
+        Some("rate") => match words.next() {
+            Some(name) => Command::Rate(name.trim_start_matches('@').to_string()),
+            None => Command::Help,
+        },
+        Some("ranking") => Command::Ranking,
+        _ => Command::Help,
+    };
+    Some(command)
+}
+
+// traQ prefixes bot user names with `BOT_`
+fn is_bot_name(name: &str, bot_name: &str) -> bool {
+    let name = name.strip_prefix("BOT_").unwrap_or(name);
+    name.eq_ignore_ascii_case(bot_name)
+}
+
+/// Answers commands mentioned to the bot on traQ.
+pub struct Bot<
+    PR: crate::domain::persist_repository::PersistRepository,
+    TB: crate::domain::traq_bot::TraqBot,
+> {
+    persist_repository: Arc<PR>,
+    traq_bot: Arc<TB>,
+    name: String,
+}
+
+impl <PR, TB> Bot<PR, TB>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+    TB: crate::domain::traq_bot::TraqBot,
+{
+    pub fn new(persist_repository: Arc<PR>, traq_bot: Arc<TB>, name: String) -> Self {
+        Self {
+            persist_repository,
+            traq_bot,
+            name,
+        }
+    }
+
+    /// Replies in `channel_id` if `text` is a command for the bot.
+    pub async fn handle_message(&self, channel_id: &str, text: &str, is_direct: bool) -> Result<()> {
+        let Some(command) = parse_command(text, &self.name, !is_direct) else {
+            return Ok(());
+        };
+        tracing::info!("Received bot command {:?} in {}", command, channel_id);
+        let reply = self.reply(&command).await?;
+        self.traq_bot.post_message(channel_id, &reply).await
+    }
+
+    async fn reply(&self, command: &Command) -> Result<String> {
+        match command {
+            Command::Rate(name) => {
+                let user = self.persist_repository
+                    .get_user(name)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to get user: {}", e))?;
+                let Some(user) = user else {
+                    return Ok(format!("{} is not a known member.", name));
+                };
+                let Some(atcoder_account_name) = &user.atcoder_account_name else {
+                    return Ok(format!("{} has not linked an AtCoder account on traPortfolio.", name));
+                };
+                let rating = |rating: Option<i32>| {
+                    rating.map_or_else(|| "-".to_string(), |rating| rating.to_string())
+                };
+                Ok(format!(
+                    "### {} ([{}](https://atcoder.jp/users/{}))\n| | Rating |\n|---|---:|\n| Algorithm | {} |\n| Heuristic | {} |\n",
+                    user.trap_account_name,
+                    atcoder_account_name,
+                    atcoder_account_name,
+                    rating(user.atcoder_rating),
+                    rating(user.heuristic_rating),
+                ))
+            }
+            Command::Ranking => {
+                let users = self.persist_repository
+                    .get_users()
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to get users: {}", e))?;
+                Ok(format!(
+                    "{}\n{}",
+                    format_ranking("Algorithm", &top_users(&users, ContestType::Algorithm, RANKING_SIZE)),
+                    format_ranking("Heuristic", &top_users(&users, ContestType::Heuristic, RANKING_SIZE)),
+                ))
+            }
+            Command::Help => Ok(HELP.replace("algo-stats", &self.name)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::dto::User;
+    use crate::domain::persist_repository::PersistRepository as _;
+    use crate::testing::{
+        in_memory_persist_repository::InMemoryPersistRepository,
+        recording_traq_bot::RecordingTraqBot,
+    };
+
+    #[test]
+    fn test_parse_command() {
+        let parse = |text| parse_command(text, "algo-stats", true);
+        assert_eq!(parse("@algo-stats rate alice"), Some(Command::Rate("alice".to_string())));
+        assert_eq!(parse("@BOT_algo-stats  RATE @alice"), Some(Command::Rate("alice".to_string())));
+        assert_eq!(parse("@algo-stats ranking"), Some(Command::Ranking));
+        assert_eq!(parse("@algo-stats rate"), Some(Command::Help));
+        assert_eq!(parse("@algo-stats"), Some(Command::Help));
+        assert_eq!(parse("rate alice"), None);
+        assert_eq!(parse("@alice rate alice"), None);
+        assert_eq!(parse_command("ranking", "algo-stats", false), Some(Command::Ranking));
+    }
+
+    async fn bot() -> (Bot<InMemoryPersistRepository, RecordingTraqBot>, Arc<RecordingTraqBot>) {
+        let persist_repository = Arc::new(InMemoryPersistRepository::default());
+        let user = |name: &str, atcoder_account_name: Option<&str>, rating| User {
+            trap_account_name: name.to_string(),
+            atcoder_account_name: atcoder_account_name.map(str::to_string),
+            atcoder_rating: rating,
+            heuristic_rating: None,
+            is_algo_team: Some(false),
+            is_active: Some(true),
+            grade: None,
+            last_fetch_error: None,
+        };
+        persist_repository
+            .set_users(vec![
+                user("alice", Some("alice_ac"), Some(412)),
+                user("bob", Some("bob_ac"), Some(1623)),
+                user("dave", None, None),
+            ])
+            .await
+            .unwrap();
+        let traq_bot = Arc::new(RecordingTraqBot::default());
+        (Bot::new(persist_repository, traq_bot.clone(), "algo-stats".to_string()), traq_bot)
+    }
+
+    #[tokio::test]
+    async fn test_handle_message() {
+        let (bot, traq_bot) = bot().await;
+        bot.handle_message("channel", "@algo-stats rate alice", false).await.unwrap();
+        bot.handle_message("channel", "@algo-stats rate dave", false).await.unwrap();
+        bot.handle_message("channel", "@algo-stats rate nobody", false).await.unwrap();
+        bot.handle_message("dm", "ranking", true).await.unwrap();
+        bot.handle_message("channel", "just chatting", false).await.unwrap();
+
+        let messages = traq_bot.messages();
+        assert_eq!(messages.len(), 4);
+        assert!(messages[0].1.contains("| Algorithm | 412 |"));
+        assert!(messages[0].1.contains("https://atcoder.jp/users/alice_ac"));
+        assert!(messages[1].1.contains("has not linked"));
+        assert!(messages[2].1.contains("not a known member"));
+        assert_eq!(messages[3].0, "dm");
+        let ranking = &messages[3].1;
+        assert!(ranking.find("| 1 | bob |").unwrap() < ranking.find("| 2 | alice |").unwrap());
+    }
+}
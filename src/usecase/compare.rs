@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use crate::domain::dto::{
+    ComparedUser, Comparison, ContestHistoryEntry, HeadToHeadContest, HeadToHeadEntry, HeadToHeadRecord, User,
+};
+use crate::domain::entity::{ContestResult, ContestType};
+
+/// Lines up the contest histories of `accounts`, each a linked user and their results.
+///
+/// Contests entered by two or more of them count as head-to-head, where a lower place wins.
+pub fn compare(contest_type: ContestType, accounts: Vec<(User, Vec<ContestResult>)>) -> Comparison {
+    let mut contests = BTreeMap::<&str, Vec<(usize, &ContestResult)>>::new();
+    for (i, (_, results)) in accounts.iter().enumerate() {
+        for result in results.iter() {
+            contests
+                .entry(result.contest_screen_name.as_str())
+                .or_default()
+                .push((i, result));
+        }
+    }
+    let shared = contests
+        .into_values()
+        .filter(|entries| entries.len() >= 2)
+        .collect::<Vec<_>>();
+
+    let mut wins = vec![0; accounts.len()];
+    let mut records = vec![];
+    for i in 0..accounts.len() {
+        for j in i + 1..accounts.len() {
+            let mut record = HeadToHeadRecord {
+                user: accounts[i].0.trap_account_name.clone(),
+                opponent: accounts[j].0.trap_account_name.clone(),
+                wins: 0,
+                losses: 0,
+                draws: 0,
+            };
+            for entries in shared.iter() {
+                let place = |k| entries.iter().find(|(index, _)| *index == k).map(|(_, result)| result.place);
+                if let (Some(a), Some(b)) = (place(i), place(j)) {
+                    match a.cmp(&b) {
+                        std::cmp::Ordering::Less => record.wins += 1,
+                        std::cmp::Ordering::Greater => record.losses += 1,
+                        std::cmp::Ordering::Equal => record.draws += 1,
+                    }
+                }
+            }
+            records.push(record);
+        }
+    }
+
+    let mut head_to_head = shared
+        .into_iter()
+        .map(|mut entries| {
+            entries.sort_by_key(|(_, result)| result.place);
+            let winner = match entries.as_slice() {
+                [(i, first), (_, second), ..] if first.place < second.place => {
+                    wins[*i] += 1;
+                    Some(accounts[*i].0.trap_account_name.clone())
+                }
+                _ => None,
+            };
+            let (_, first) = entries[0];
+            HeadToHeadContest {
+                contest_screen_name: first.contest_screen_name.clone(),
+                contest_name: first.contest_name.clone(),
+                end_time: first.end_time,
+                entries: entries
+                    .iter()
+                    .map(|(i, result)| HeadToHeadEntry {
+                        trap_account_name: accounts[*i].0.trap_account_name.clone(),
+                        place: result.place,
+                        performance: result.performance,
+                        rating_delta: result.diff,
+                    })
+                    .collect(),
+                winner,
+            }
+        })
+        .collect::<Vec<_>>();
+    head_to_head.sort_by_key(|contest| std::cmp::Reverse(contest.end_time));
+
+    let users = accounts
+        .into_iter()
+        .zip(wins)
+        .map(|((user, results), wins)| ComparedUser {
+            rating: user.rating(contest_type),
+            trap_account_name: user.trap_account_name,
+            atcoder_account_name: user.atcoder_account_name.unwrap_or_default(),
+            timeline: results
+                .into_iter()
+                .filter(|result| result.is_rated)
+                .map(ContestHistoryEntry::from)
+                .collect(),
+            wins,
+        })
+        .collect();
+    Comparison {
+        contest_type: contest_type.as_str().to_string(),
+        users,
+        head_to_head,
+        records,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(name: &str, rating: i32) -> User {
+        User {
+            trap_account_name: name.to_string(),
+            atcoder_account_name: Some(format!("{}_ac", name)),
+            atcoder_rating: Some(rating),
+            heuristic_rating: None,
+            is_algo_team: Some(true),
+            is_active: Some(true),
+            grade: None,
+            last_fetch_error: None,
+        }
+    }
+
+    fn result(contest: &str, days_ago: i64, place: i32, is_rated: bool) -> ContestResult {
+        ContestResult {
+            is_rated,
+            place,
+            old_rating: 1000,
+            new_rating: 1000 + 10 * (100 - place),
+            diff: 10 * (100 - place),
+            performance: 2000 - place,
+            contest_screen_name: format!("{}.contest.atcoder.jp", contest),
+            contest_name: contest.to_uppercase(),
+            end_time: chrono::Utc::now() - chrono::Duration::days(days_ago),
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let comparison = compare(ContestType::Algorithm, vec![
+            (user("alice", 1200), vec![result("abc398", 30, 50, true), result("abc399", 20, 300, true)]),
+            (user("bob", 1100), vec![
+                result("abc398", 30, 80, true),
+                result("abc399", 20, 100, false),
+                result("abc400", 10, 5, true),
+            ]),
+            (user("carol", 900), vec![result("abc399", 20, 100, true)]),
+        ]);
+        let contests = comparison.head_to_head
+            .iter()
+            .map(|contest| contest.contest_name.as_str())
+            .collect::<Vec<_>>();
+        // abc400 had only bob, and newest comes first
+        assert_eq!(contests, vec!["ABC399", "ABC398"]);
+        // bob and carol tied for first in abc399
+        assert_eq!(comparison.head_to_head[0].winner, None);
+        assert_eq!(comparison.head_to_head[0].entries[2].trap_account_name, "alice");
+        assert_eq!(comparison.head_to_head[1].winner.as_deref(), Some("alice"));
+        let wins = comparison.users
+            .iter()
+            .map(|user| user.wins)
+            .collect::<Vec<_>>();
+        assert_eq!(wins, vec![1, 0, 0]);
+        // Unrated contests are not on the timeline
+        assert_eq!(comparison.users[1].timeline.len(), 2);
+
+        let record = |user: &str, opponent: &str| {
+            comparison.records
+                .iter()
+                .find(|record| record.user == user && record.opponent == opponent)
+                .map(|record| (record.wins, record.losses, record.draws))
+                .unwrap()
+        };
+        assert_eq!(record("alice", "bob"), (1, 1, 0));
+        assert_eq!(record("alice", "carol"), (0, 1, 0));
+        assert_eq!(record("bob", "carol"), (0, 0, 1));
+    }
+}
@@ -0,0 +1,122 @@
+use anyhow::Result;
+use crate::domain::dto::{ContestParticipant, ContestReport};
+
+// The history and results APIs name contests by their old subdomain
+static CONTEST_SCREEN_NAME_SUFFIX: &str = ".contest.atcoder.jp";
+
+/// Accepts both `abc400` and `abc400.contest.atcoder.jp`.
+pub fn normalize_screen_name(screen_name: &str) -> String {
+    if screen_name.contains('.') {
+        screen_name.to_string()
+    } else {
+        format!("{}{}", screen_name, CONTEST_SCREEN_NAME_SUFFIX)
+    }
+}
+
+/// Summarizes how traP members did in a contest, or `None` if none of them took part.
+pub async fn get_contest_report<PR>(persist_repository: &PR, screen_name: &str) -> Result<Option<ContestReport>>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    let contest_screen_name = normalize_screen_name(screen_name);
+    let participations = persist_repository
+        .get_contest_participations(&contest_screen_name)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to get contest participations: {}", e))?;
+    let Some(first) = participations.first() else {
+        return Ok(None);
+    };
+    let contest_name = first.result.contest_name.clone();
+    let contest_type = first.contest_type;
+    let end_time = first.result.end_time;
+    let participants = participations
+        .into_iter()
+        .map(|participation| ContestParticipant {
+            trap_account_name: participation.trap_account_name,
+            atcoder_account_name: participation.atcoder_account_name,
+            is_rated: participation.result.is_rated,
+            place: participation.result.place,
+            performance: participation.result.performance,
+            old_rating: participation.result.old_rating,
+            new_rating: participation.result.new_rating,
+            rating_delta: participation.result.diff,
+        })
+        .collect::<Vec<_>>();
+    // Places break ties in performance
+    let best_performer = participants
+        .iter()
+        .min_by_key(|participant| (-participant.performance, participant.place))
+        .cloned();
+    Ok(Some(ContestReport {
+        contest_screen_name,
+        contest_name,
+        contest_type: contest_type.as_str().to_string(),
+        end_time,
+        participants,
+        best_performer,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::dto::User;
+    use crate::domain::entity::{ContestResult, ContestType};
+    use crate::domain::persist_repository::PersistRepository as _;
+    use crate::testing::in_memory_persist_repository::InMemoryPersistRepository;
+
+    fn result(place: i32, performance: i32, old_rating: i32, new_rating: i32) -> ContestResult {
+        ContestResult {
+            is_rated: true,
+            place,
+            old_rating,
+            new_rating,
+            diff: new_rating - old_rating,
+            performance,
+            contest_screen_name: "abc400.contest.atcoder.jp".to_string(),
+            contest_name: "AtCoder Beginner Contest 400".to_string(),
+            end_time: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_contest_report() {
+        let persist_repository = InMemoryPersistRepository::default();
+        let user = |name: &str| User {
+            trap_account_name: name.to_string(),
+            atcoder_account_name: Some(format!("{}_ac", name)),
+            atcoder_rating: None,
+            heuristic_rating: None,
+            is_algo_team: None,
+            is_active: None,
+            grade: None,
+            last_fetch_error: None,
+        };
+        persist_repository.set_users(vec![user("alice"), user("bob")]).await.unwrap();
+        let results = [
+            ("alice_ac", result(900, 1200, 412, 500)),
+            ("bob_ac", result(300, 1900, 1623, 1701)),
+            ("stranger", result(1, 3000, 2800, 2900)),
+        ];
+        for (name, result) in results {
+            persist_repository
+                .set_contest_results(name, ContestType::Algorithm, vec![result])
+                .await
+                .unwrap();
+        }
+
+        let report = get_contest_report(&persist_repository, "abc400").await.unwrap().unwrap();
+        assert_eq!(report.contest_screen_name, "abc400.contest.atcoder.jp");
+        assert_eq!(report.contest_type, "algorithm");
+        let names = report.participants
+            .iter()
+            .map(|participant| participant.trap_account_name.as_str())
+            .collect::<Vec<_>>();
+        // Non-members are left out
+        assert_eq!(names, vec!["bob", "alice"]);
+        assert_eq!(report.participants[1].rating_delta, 88);
+        assert_eq!(report.best_performer.unwrap().trap_account_name, "bob");
+
+        assert!(get_contest_report(&persist_repository, "abc401").await.unwrap().is_none());
+    }
+}
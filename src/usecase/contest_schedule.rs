@@ -0,0 +1,234 @@
+use std::sync::Arc;
+use anyhow::Result;
+use tokio_util::sync::CancellationToken;
+
+pub struct ContestScheduleConfig {
+    /// How often the upcoming contests are fetched.
+    pub refresh_cron: String,
+    /// How often due reminders are looked for.
+    pub reminder_cron: String,
+}
+
+/// Keeps the upcoming contests in sync with AtCoder and reminds traQ of them.
+pub struct ContestSchedule<
+    CS: crate::domain::contest_schedule_fetcher::ContestScheduleFetcher,
+    PR: crate::domain::persist_repository::PersistRepository,
+    NT: crate::domain::notifier::Notifier,
+> {
+    fetcher: CS,
+    persist_repository: Arc<PR>,
+    notifier: Option<NT>,
+    reminder_offsets: Vec<chrono::Duration>,
+}
+
+impl <CS, PR, NT> ContestSchedule<CS, PR, NT>
+where
+    CS: crate::domain::contest_schedule_fetcher::ContestScheduleFetcher,
+    PR: crate::domain::persist_repository::PersistRepository,
+    NT: crate::domain::notifier::Notifier,
+{
+    /// A reminder is posted `offset` before the start of each contest for every offset.
+    pub fn new(fetcher: CS, persist_repository: Arc<PR>, reminder_offsets: Vec<chrono::Duration>) -> Self {
+        Self {
+            fetcher,
+            persist_repository,
+            notifier: None,
+            reminder_offsets,
+        }
+    }
+
+    /// Posts reminders through `notifier`. Without one, contests are only fetched.
+    pub fn with_notifier(mut self, notifier: NT) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Refreshes the schedule and posts reminders on `config` until `shutdown` is cancelled.
+    pub async fn serve(self: Arc<Self>, config: ContestScheduleConfig, shutdown: CancellationToken) -> Result<()> {
+        let mut scheduler = tokio_cron_scheduler::JobScheduler::new()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create scheduler: {}", e))?;
+        let schedule = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = schedule.refresh().await {
+                tracing::error!("Failed to refresh contest schedule: {}", e);
+            }
+        });
+        let schedule = self.clone();
+        scheduler
+            .add(
+                tokio_cron_scheduler::Job::new_async(config.refresh_cron.as_str(), move |_, _| {
+                    let schedule = schedule.clone();
+                    Box::pin(async move {
+                        if let Err(e) = schedule.refresh().await {
+                            tracing::error!("Failed to refresh contest schedule: {}", e);
+                        }
+                    })
+                })
+                    .map_err(|e| anyhow::anyhow!("Failed to create job: {}", e))?
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to add job: {}", e))?;
+        let schedule = self.clone();
+        scheduler
+            .add(
+                tokio_cron_scheduler::Job::new_async(config.reminder_cron.as_str(), move |_, _| {
+                    let schedule = schedule.clone();
+                    Box::pin(async move {
+                        if let Err(e) = schedule.send_reminders(chrono::Utc::now()).await {
+                            tracing::error!("Failed to send contest reminders: {}", e);
+                        }
+                    })
+                })
+                    .map_err(|e| anyhow::anyhow!("Failed to create job: {}", e))?
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to add job: {}", e))?;
+        scheduler
+            .start()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to start scheduler: {}", e))?;
+        tracing::info!(
+            "Scheduled contest schedule refresh with {} and reminders with {}",
+            config.refresh_cron,
+            config.reminder_cron
+        );
+        shutdown.cancelled().await;
+        tracing::info!("Shutting down contest schedule");
+        scheduler
+            .shutdown()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to shut down scheduler: {}", e))?;
+        Ok(())
+    }
+
+    pub async fn refresh(&self) -> Result<()> {
+        let contests = self.fetcher
+            .get_upcoming_contests()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get upcoming contests: {}", e))?;
+        tracing::info!("Found {} upcoming contests", contests.len());
+        self.persist_repository
+            .set_contests(contests)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to set contests: {}", e))?;
+        Ok(())
+    }
+
+    /// Posts the reminders that are due at `now` and have not been posted yet.
+    ///
+    /// When several offsets fall due at once, e.g. for a contest announced at short notice,
+    /// only one reminder is posted and the earlier offsets are skipped.
+    pub async fn send_reminders(&self, now: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        let Some(notifier) = &self.notifier else {
+            return Ok(());
+        };
+        let Some(max_offset) = self.reminder_offsets.iter().max() else {
+            return Ok(());
+        };
+        let contests = self.persist_repository
+            .get_contests_starting_after(now)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get upcoming contests: {}", e))?;
+        for contest in contests {
+            let starts_in = contest.start_time - now;
+            if starts_in > *max_offset {
+                break;
+            }
+            let mut due_offsets = self.reminder_offsets
+                .iter()
+                .map(|offset| offset.num_minutes())
+                .filter(|offset| starts_in <= chrono::Duration::minutes(*offset))
+                .collect::<Vec<_>>();
+            due_offsets.sort();
+            due_offsets.dedup();
+            let mut claimed = vec![];
+            for offset in due_offsets.iter() {
+                let is_new = self.persist_repository
+                    .claim_contest_reminder(&contest.contest_id, *offset)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to claim contest reminder: {}", e))?;
+                if is_new {
+                    claimed.push(*offset);
+                }
+            }
+            // The nearest offset has been reminded of already, the rest are stale
+            if due_offsets.first().is_none_or(|nearest| !claimed.contains(nearest)) {
+                continue;
+            }
+            tracing::info!("Reminding of {} starting in {}", contest.contest_id, starts_in);
+            if let Err(e) = notifier.notify_contest_reminder(&contest, starts_in).await {
+                for offset in claimed {
+                    self.persist_repository
+                        .release_contest_reminder(&contest.contest_id, offset)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("Failed to release contest reminder: {}", e))?;
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::{
+        contest_schedule_fetcher::ContestScheduleFetcherImpl,
+        traq_notifier::{TraqNotifierChannels, TraqNotifierImpl},
+    };
+    use crate::testing::{
+        fake_server::{FakeServer, atcoder_router},
+        in_memory_persist_repository::InMemoryPersistRepository,
+        recording_traq_bot::RecordingTraqBot,
+    };
+
+    #[tokio::test]
+    async fn test_send_reminders() {
+        let server = FakeServer::start(atcoder_router()).await;
+        let persist_repository = Arc::new(InMemoryPersistRepository::default());
+        let traq_bot = Arc::new(RecordingTraqBot::default());
+        let schedule = ContestSchedule::new(
+            ContestScheduleFetcherImpl::new(server.config()),
+            persist_repository.clone(),
+            vec![chrono::Duration::days(1), chrono::Duration::minutes(30)],
+        )
+            .with_notifier(TraqNotifierImpl::new(traq_bot.clone(), TraqNotifierChannels {
+                contest_reminders: Some("channel".to_string()),
+                ..Default::default()
+            }));
+        schedule.refresh().await.unwrap();
+        let at = |s| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .unwrap()
+                .with_timezone(&chrono::Utc)
+        };
+        let reminded = || {
+            traq_bot
+                .messages()
+                .into_iter()
+                .map(|(_, content)| content.lines().next().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // abc401 starts at 2025-04-19 21:00 JST
+        schedule.send_reminders(at("2025-04-18T20:00:00+09:00")).await.unwrap();
+        assert!(reminded().is_empty());
+        // The cron fires at second 0 but the clock is read a little later
+        schedule.send_reminders(at("2025-04-18T21:00:03+09:00")).await.unwrap();
+        schedule.send_reminders(at("2025-04-18T21:01:00+09:00")).await.unwrap();
+        assert_eq!(reminded().len(), 1);
+        assert!(reminded()[0].ends_with("starts in 1 day"));
+        schedule.send_reminders(at("2025-04-19T20:30:02+09:00")).await.unwrap();
+        // arc196 starts at 2025-04-20 21:00 JST
+        schedule.send_reminders(at("2025-04-19T21:00:00+09:00")).await.unwrap();
+        assert_eq!(reminded().len(), 3);
+        assert!(reminded()[1].contains("AtCoder Beginner Contest 401") && reminded()[1].ends_with("30 minutes"));
+        assert!(reminded()[2].contains("AtCoder Regular Contest 196") && reminded()[2].ends_with("1 day"));
+        // Coming back after a long pause posts one reminder, not one per offset
+        schedule.send_reminders(at("2025-04-26T14:50:00+09:00")).await.unwrap();
+        assert_eq!(reminded().len(), 4);
+        assert!(reminded()[3].contains("AtCoder Heuristic Contest 046") && reminded()[3].ends_with("10 minutes"));
+    }
+}
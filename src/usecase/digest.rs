@@ -0,0 +1,252 @@
+use std::{collections::HashMap, sync::Arc};
+use anyhow::Result;
+use crate::domain::dto::{RatingSnapshot, User};
+use crate::domain::entity::ContestType;
+use super::ranking::{format_ranking, top_users};
+
+const RANKING_SIZE: usize = 10;
+const GAINERS_SIZE: usize = 5;
+
+fn jst_date(at: chrono::DateTime<chrono::Utc>) -> String {
+    let jst = chrono::FixedOffset::east_opt(9 * 3600).expect("JST offset is valid");
+    at.with_timezone(&jst).format("%Y-%m-%d").to_string()
+}
+
+fn contest_types() -> [(ContestType, &'static str); 2] {
+    [(ContestType::Algorithm, "Algorithm"), (ContestType::Heuristic, "Heuristic")]
+}
+
+/// Formats the ranking post of an update run.
+///
+/// `previous` holds each user's latest snapshot before the run. It is empty on the first run,
+/// in which case gainers and new participants are left out since everyone would be new.
+pub fn format_digest(
+    run_at: chrono::DateTime<chrono::Utc>,
+    users: &[User],
+    previous: &HashMap<String, RatingSnapshot>,
+) -> String {
+    let mut s = format!("## AtCoder ranking as of {}\n", jst_date(run_at));
+    for (contest_type, title) in contest_types() {
+        s.push('\n');
+        s.push_str(&format_ranking(
+            &format!("{} top {}", title, RANKING_SIZE),
+            &top_users(users, contest_type, RANKING_SIZE),
+        ));
+    }
+    if previous.is_empty() {
+        return s;
+    }
+    let previous_rating = |user: &User, contest_type| {
+        previous
+            .get(&user.trap_account_name)
+            .and_then(|snapshot| match contest_type {
+                ContestType::Algorithm => snapshot.atcoder_rating,
+                ContestType::Heuristic => snapshot.heuristic_rating,
+            })
+            .unwrap_or(0)
+    };
+    // Ratings of 0 are unrated, see `top_users`
+    let rated = top_users(users, ContestType::Algorithm, usize::MAX)
+        .into_iter()
+        .map(|(user, rating)| (ContestType::Algorithm, user, rating))
+        .chain(
+            top_users(users, ContestType::Heuristic, usize::MAX)
+                .into_iter()
+                .map(|(user, rating)| (ContestType::Heuristic, user, rating)),
+        )
+        .map(|(contest_type, user, rating)| (contest_type, user, previous_rating(user, contest_type), rating))
+        .collect::<Vec<_>>();
+
+    s.push_str("\n### Biggest gainers\n");
+    let mut gainers = rated
+        .iter()
+        .filter(|(_, _, old, new)| *old > 0 && new > old)
+        .collect::<Vec<_>>();
+    gainers.sort_by(|(_, a, a_old, a_new), (_, b, b_old, b_new)| {
+        (b_new - b_old)
+            .cmp(&(a_new - a_old))
+            .then_with(|| a.trap_account_name.cmp(&b.trap_account_name))
+    });
+    gainers.truncate(GAINERS_SIZE);
+    if gainers.is_empty() {
+        s.push_str("No one gained rating since the last update.\n");
+    } else {
+        s.push_str("| traQ | Type | Rating | Diff |\n|---|---|---:|---:|\n");
+        for (contest_type, user, old, new) in gainers {
+            s.push_str(&format!(
+                "| {} | {} | {} → {} | +{} |\n",
+                user.trap_account_name,
+                contest_type.as_str(),
+                old,
+                new,
+                new - old,
+            ));
+        }
+    }
+
+    s.push_str("\n### New participants\n");
+    let newcomers = rated
+        .iter()
+        .filter(|(_, _, old, _)| *old == 0)
+        .collect::<Vec<_>>();
+    if newcomers.is_empty() {
+        s.push_str("No new participants since the last update.\n");
+    } else {
+        for (contest_type, user, _, new) in newcomers {
+            s.push_str(&format!(
+                "- {} got their first {} rating: {}\n",
+                user.trap_account_name,
+                contest_type.as_str(),
+                new,
+            ));
+        }
+    }
+    s
+}
+
+/// Posts a ranking to a traQ channel after each update run.
+pub struct Digest<
+    PR: crate::domain::persist_repository::PersistRepository,
+    TB: crate::domain::traq_bot::TraqBot,
+> {
+    persist_repository: Arc<PR>,
+    traq_bot: Arc<TB>,
+    channel_id: String,
+}
+
+impl <PR, TB> Digest<PR, TB>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+    TB: crate::domain::traq_bot::TraqBot,
+{
+    pub fn new(persist_repository: Arc<PR>, traq_bot: Arc<TB>, channel_id: String) -> Self {
+        Self {
+            persist_repository,
+            traq_bot,
+            channel_id,
+        }
+    }
+
+    /// Posts the digest of the last update run unless it has already been posted.
+    pub async fn post_for_last_run(&self) -> Result<()> {
+        let Some(run) = self.persist_repository
+            .get_last_update_run()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get last update run: {}", e))?
+        else {
+            return Ok(());
+        };
+        let claimed = self.persist_repository
+            .claim_digest(run.id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to claim digest: {}", e))?;
+        if !claimed {
+            tracing::info!("Digest of update run {} has already been posted", run.id);
+            return Ok(());
+        }
+        let result = self.post(run.started_at).await;
+        if result.is_err() {
+            self.persist_repository
+                .release_digest(run.id)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to release digest: {}", e))?;
+        }
+        result
+    }
+
+    async fn post(&self, run_at: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        let users = self.persist_repository
+            .get_users()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get users: {}", e))?;
+        let previous = self.persist_repository
+            .get_latest_rating_snapshots_before(run_at)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get previous snapshots: {}", e))?
+            .into_iter()
+            .map(|snapshot| (snapshot.trap_account_name.clone(), snapshot))
+            .collect::<HashMap<_, _>>();
+        let content = format_digest(run_at, &users, &previous);
+        self.traq_bot.post_message(&self.channel_id, &content).await?;
+        tracing::info!("Posted digest to {}", self.channel_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entity::UpdateMode;
+    use crate::domain::persist_repository::PersistRepository as _;
+    use crate::testing::{
+        in_memory_persist_repository::InMemoryPersistRepository,
+        recording_traq_bot::RecordingTraqBot,
+    };
+
+    fn user(name: &str, atcoder_rating: i32, heuristic_rating: i32) -> User {
+        User {
+            trap_account_name: name.to_string(),
+            atcoder_account_name: Some(format!("{}_ac", name)),
+            atcoder_rating: Some(atcoder_rating),
+            heuristic_rating: Some(heuristic_rating),
+            is_algo_team: Some(true),
+            is_active: Some(true),
+            grade: None,
+            last_fetch_error: None,
+        }
+    }
+
+    #[test]
+    fn test_format_digest() {
+        let run_at = chrono::DateTime::parse_from_rfc3339("2025-04-14T04:00:00+09:00")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let users = vec![
+            user("alice", 412, 693),
+            user("bob", 1701, 0),
+            user("carol", 1200, 0),
+        ];
+        let first = format_digest(run_at, &users, &HashMap::new());
+        assert!(first.starts_with("## AtCoder ranking as of 2025-04-14\n"));
+        assert!(first.contains("| 1 | bob | bob_ac | 1701 |"));
+        assert!(!first.contains("Biggest gainers"));
+
+        let previous = [("alice", 60, 0), ("bob", 1623, 0), ("carol", 1250, 0)]
+            .into_iter()
+            .map(|(name, atcoder_rating, heuristic_rating)| {
+                (name.to_string(), RatingSnapshot {
+                    trap_account_name: name.to_string(),
+                    taken_at: run_at - chrono::Duration::days(7),
+                    atcoder_rating: Some(atcoder_rating),
+                    heuristic_rating: Some(heuristic_rating),
+                })
+            })
+            .collect::<HashMap<_, _>>();
+        let digest = format_digest(run_at, &users, &previous);
+        let gainers = &digest[digest.find("### Biggest gainers").unwrap()..];
+        assert!(gainers.find("| alice | algorithm | 60 → 412 | +352 |").unwrap()
+            < gainers.find("| bob | algorithm | 1623 → 1701 | +78 |").unwrap());
+        assert!(!gainers.contains("carol |"));
+        assert!(digest.contains("- alice got their first heuristic rating: 693"));
+    }
+
+    #[tokio::test]
+    async fn test_post_for_last_run_is_idempotent() {
+        let persist_repository = Arc::new(InMemoryPersistRepository::default());
+        let traq_bot = Arc::new(RecordingTraqBot::default());
+        let digest = Digest::new(persist_repository.clone(), traq_bot.clone(), "channel".to_string());
+        // Nothing to post before the first run
+        digest.post_for_last_run().await.unwrap();
+        assert!(traq_bot.messages().is_empty());
+
+        let now = chrono::Utc::now();
+        persist_repository.set_users(vec![user("alice", 412, 693)]).await.unwrap();
+        persist_repository.add_update_run(UpdateMode::User, now, now).await.unwrap();
+        digest.post_for_last_run().await.unwrap();
+        digest.post_for_last_run().await.unwrap();
+        let messages = traq_bot.messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].0, "channel");
+        assert!(messages[0].1.contains("| 1 | alice | alice_ac | 412 |"));
+    }
+}
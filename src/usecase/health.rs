@@ -0,0 +1,69 @@
+use crate::domain::dto::Readiness;
+
+/// Ready when the database answers. Data older than `max_data_age` is only flagged as stale, so
+/// that a broken upstream does not take the API out of rotation.
+pub async fn check_readiness<PR>(
+    persist_repository: &PR,
+    max_data_age: chrono::Duration,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Readiness
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    let last_run = match persist_repository.ping().await {
+        Ok(()) => persist_repository.get_last_update_run().await,
+        Err(e) => Err(e),
+    };
+    let (database_reachable, last_update_at) = match last_run {
+        Ok(run) => (true, run.map(|run| run.finished_at)),
+        Err(e) => {
+            tracing::warn!("Database is not reachable: {}", e);
+            (false, None)
+        }
+    };
+    let is_stale = last_update_at.is_none_or(|finished_at| now - finished_at > max_data_age);
+    Readiness {
+        ready: database_reachable,
+        database_reachable,
+        last_update_at,
+        is_stale,
+        max_data_age_seconds: max_data_age.num_seconds(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entity::UpdateMode;
+    use crate::domain::persist_repository::PersistRepository as _;
+    use crate::testing::in_memory_persist_repository::InMemoryPersistRepository;
+
+    #[tokio::test]
+    async fn test_check_readiness() {
+        let persist_repository = InMemoryPersistRepository::default();
+        let now = chrono::Utc::now();
+        let max_data_age = chrono::Duration::days(8);
+
+        // Nothing has been fetched yet
+        let readiness = check_readiness(&persist_repository, max_data_age, now).await;
+        assert!(readiness.ready && readiness.is_stale);
+
+        let finished_at = now - chrono::Duration::days(7);
+        persist_repository
+            .add_update_run(UpdateMode::Contest, finished_at - chrono::Duration::minutes(5), finished_at)
+            .await
+            .unwrap();
+        let readiness = check_readiness(&persist_repository, max_data_age, now).await;
+        assert!(readiness.ready && !readiness.is_stale);
+        assert_eq!(readiness.last_update_at, Some(finished_at));
+        assert_eq!(readiness.max_data_age_seconds, 8 * 24 * 60 * 60);
+
+        let readiness = check_readiness(&persist_repository, max_data_age, now + chrono::Duration::days(2)).await;
+        assert!(readiness.ready && readiness.is_stale);
+
+        persist_repository.state.lock().unwrap().is_unreachable = true;
+        let readiness = check_readiness(&persist_repository, max_data_age, now).await;
+        assert!(!readiness.database_reachable && !readiness.ready);
+        assert_eq!(readiness.last_update_at, None);
+    }
+}
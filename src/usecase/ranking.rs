@@ -0,0 +1,78 @@
+use crate::domain::dto::User;
+use crate::domain::entity::ContestType;
+
+/// Active users rated in `contest_type`, highest first and ties broken by name.
+///
+/// A rating of 0 means the account has never entered a rated contest, so it is left out.
+pub fn top_users(users: &[User], contest_type: ContestType, limit: usize) -> Vec<(&User, i32)> {
+    let mut ranked = users
+        .iter()
+        .filter(|user| user.is_active.unwrap_or(false))
+        .filter_map(|user| Some((user, user.rating(contest_type).filter(|rating| *rating > 0)?)))
+        .collect::<Vec<_>>();
+    ranked.sort_by(|(a, a_rating), (b, b_rating)| {
+        b_rating
+            .cmp(a_rating)
+            .then_with(|| a.trap_account_name.cmp(&b.trap_account_name))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Renders a ranking as a Markdown table.
+///
+/// Names are written without `@` so that posting the table does not notify everyone in it.
+pub fn format_ranking(title: &str, ranked: &[(&User, i32)]) -> String {
+    let mut s = format!("### {}\n", title);
+    if ranked.is_empty() {
+        s.push_str("No rated members yet.\n");
+        return s;
+    }
+    s.push_str("| # | traQ | AtCoder | Rating |\n|---:|---|---|---:|\n");
+    for (i, (user, rating)) in ranked.iter().enumerate() {
+        s.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            i + 1,
+            user.trap_account_name,
+            user.atcoder_account_name.as_deref().unwrap_or("-"),
+            rating,
+        ));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(name: &str, rating: Option<i32>, is_active: bool) -> User {
+        User {
+            trap_account_name: name.to_string(),
+            atcoder_account_name: Some(format!("{}_ac", name)),
+            atcoder_rating: rating,
+            heuristic_rating: None,
+            is_algo_team: Some(false),
+            is_active: Some(is_active),
+            grade: None,
+            last_fetch_error: None,
+        }
+    }
+
+    #[test]
+    fn test_top_users() {
+        let users = vec![
+            user("alice", Some(400), true),
+            user("bob", Some(1600), true),
+            user("carol", Some(400), true),
+            user("dave", Some(2800), false),
+            user("eve", None, true),
+            user("frank", Some(0), true),
+        ];
+        let names = top_users(&users, ContestType::Algorithm, 2)
+            .into_iter()
+            .map(|(user, rating)| (user.trap_account_name.as_str(), rating))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![("bob", 1600), ("alice", 400)]);
+        assert!(top_users(&users, ContestType::Heuristic, 10).is_empty());
+    }
+}
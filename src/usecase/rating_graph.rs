@@ -0,0 +1,199 @@
+use crate::domain::entity::ContestResult;
+use crate::domain::rating_color::{COLOR_WIDTH, RatingColor};
+use super::svg::escape_xml;
+
+const MARGIN_LEFT: f64 = 44.0;
+const MARGIN_RIGHT: f64 = 12.0;
+const MARGIN_TOP: f64 = 12.0;
+const MARGIN_BOTTOM: f64 = 28.0;
+// Room for the names when several series are drawn
+const LEGEND_HEIGHT: f64 = 20.0;
+// Roughly one date label per this many pixels
+const X_LABEL_SPACING: f64 = 110.0;
+const SINGLE_LINE_COLOR: &str = "#888";
+// Distinguishable on every band, picked for several series
+const LINE_COLORS: [&str; 6] = ["#222", "#d62728", "#1f77b4", "#2ca02c", "#9467bd", "#e377c2"];
+
+pub struct GraphPoint {
+    pub at: chrono::DateTime<chrono::Utc>,
+    pub rating: i32,
+    /// Shown on hover, usually the contest name.
+    pub label: String,
+}
+
+/// The rating timeline of one account.
+pub struct GraphSeries {
+    pub name: String,
+    pub points: Vec<GraphPoint>,
+}
+
+impl GraphSeries {
+    /// Plots the rated contests of `results`, which must be oldest first.
+    pub fn from_results(name: String, results: &[ContestResult]) -> Self {
+        GraphSeries {
+            name,
+            points: results
+                .iter()
+                .filter(|result| result.is_rated)
+                .map(|result| GraphPoint {
+                    at: result.end_time,
+                    rating: result.new_rating,
+                    label: result.contest_name.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Renders rating timelines over AtCoder's color bands like the chart on AtCoder's profile pages.
+///
+/// Points are filled in the color of their rating. With more than one series, lines get
+/// distinct colors and a legend is drawn on top.
+pub fn render_rating_graph(series: &[GraphSeries], width: u32, height: u32) -> String {
+    let (width, height) = (width as f64, height as f64);
+    let is_multi = series.len() > 1;
+    let top = MARGIN_TOP + if is_multi { LEGEND_HEIGHT } else { 0.0 };
+    let (left, right, bottom) = (MARGIN_LEFT, width - MARGIN_RIGHT, height - MARGIN_BOTTOM);
+    let mut s = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="10">"#,
+        width, height,
+    );
+    s.push_str(&format!(r##"<rect width="{}" height="{}" fill="#fff"/>"##, width, height));
+
+    let points = series
+        .iter()
+        .flat_map(|series| series.points.iter())
+        .collect::<Vec<_>>();
+    let (Some(first), Some(last), Some(lowest), Some(highest)) = (
+        points.iter().map(|point| point.at).min(),
+        points.iter().map(|point| point.at).max(),
+        points.iter().map(|point| point.rating).min(),
+        points.iter().map(|point| point.rating).max(),
+    ) else {
+        s.push_str(&format!(
+            r##"<text x="{}" y="{}" text-anchor="middle" fill="#888">No rated contests</text></svg>"##,
+            width / 2.0,
+            height / 2.0,
+        ));
+        return s;
+    };
+
+    // Whole color bands with some headroom around the extremes
+    let y_min = (lowest - 100).max(0) / COLOR_WIDTH * COLOR_WIDTH;
+    let y_max = ((highest + 100 + COLOR_WIDTH - 1) / COLOR_WIDTH * COLOR_WIDTH).max(y_min + COLOR_WIDTH);
+    let y = |rating: i32| bottom - (rating - y_min) as f64 / (y_max - y_min) as f64 * (bottom - top);
+    // A single contest gets a month on either side
+    let padding = ((last - first) / 30).max(chrono::Duration::days(30));
+    let (t_min, t_max) = (first - padding, last + padding);
+    let x = |at: chrono::DateTime<chrono::Utc>| {
+        left + (at - t_min).num_seconds() as f64 / (t_max - t_min).num_seconds() as f64 * (right - left)
+    };
+
+    for lower in (y_min..y_max).step_by(COLOR_WIDTH as usize) {
+        s.push_str(&format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="0.2"/>"#,
+            left,
+            y(lower + COLOR_WIDTH),
+            right - left,
+            y(lower) - y(lower + COLOR_WIDTH),
+            RatingColor::from_rating(lower).hex(),
+        ));
+    }
+    for rating in (y_min..=y_max).step_by(COLOR_WIDTH as usize) {
+        s.push_str(&format!(
+            concat!(
+                r##"<line x1="{0}" y1="{1}" x2="{2}" y2="{1}" stroke="#fff"/>"##,
+                r##"<text x="{3}" y="{4}" text-anchor="end" fill="#555">{5}</text>"##,
+            ),
+            left, y(rating), right, left - 4.0, y(rating) + 3.5, rating,
+        ));
+    }
+    let x_labels = ((right - left) / X_LABEL_SPACING).floor().max(1.0) as i32;
+    for i in 0..=x_labels {
+        let at = t_min + (t_max - t_min) * i / x_labels;
+        s.push_str(&format!(
+            r##"<text x="{}" y="{}" text-anchor="middle" fill="#555">{}</text>"##,
+            x(at),
+            bottom + 16.0,
+            at.format("%Y/%m"),
+        ));
+    }
+    s.push_str(&format!(
+        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#aaa"/>"##,
+        left, top, right - left, bottom - top,
+    ));
+
+    for (i, timeline) in series.iter().enumerate() {
+        let line_color = if is_multi { LINE_COLORS[i % LINE_COLORS.len()] } else { SINGLE_LINE_COLOR };
+        let polyline = timeline.points
+            .iter()
+            .map(|point| format!("{:.1},{:.1}", x(point.at), y(point.rating)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        s.push_str(&format!(
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+            polyline, line_color,
+        ));
+        for point in timeline.points.iter() {
+            s.push_str(&format!(
+                r##"<circle cx="{:.1}" cy="{:.1}" r="3.5" fill="{}" stroke="{}"><title>{}: {} ({})</title></circle>"##,
+                x(point.at),
+                y(point.rating),
+                RatingColor::from_rating(point.rating).hex(),
+                if is_multi { line_color } else { "#fff" },
+                escape_xml(&timeline.name),
+                point.rating,
+                escape_xml(&point.label),
+            ));
+        }
+        if is_multi {
+            let legend_x = left + i as f64 * (right - left) / series.len() as f64;
+            s.push_str(&format!(
+                concat!(
+                    r#"<rect x="{0}" y="{1}" width="12" height="3" fill="{2}"/>"#,
+                    r##"<text x="{3}" y="{4}" fill="#333">{5}</text>"##,
+                ),
+                legend_x, MARGIN_TOP + 4.0, line_color, legend_x + 16.0, MARGIN_TOP + 8.0, escape_xml(&timeline.name),
+            ));
+        }
+    }
+    s.push_str("</svg>");
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(days_ago: i64, new_rating: i32, is_rated: bool) -> ContestResult {
+        ContestResult {
+            is_rated,
+            place: 100,
+            old_rating: 0,
+            new_rating,
+            diff: new_rating,
+            performance: new_rating,
+            contest_screen_name: format!("abc{}.contest.atcoder.jp", days_ago),
+            contest_name: format!("Contest & {}", days_ago),
+            end_time: chrono::Utc::now() - chrono::Duration::days(days_ago),
+        }
+    }
+
+    #[test]
+    fn test_render_rating_graph() {
+        let results = vec![result(90, 412, true), result(60, 999, false), result(30, 1701, true)];
+        let svg = render_rating_graph(&[GraphSeries::from_results("alice".to_string(), &results)], 640, 360);
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>"));
+        // Unrated contests are left out
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert!(svg.contains("<title>alice: 1701 (Contest &amp; 30)</title>"));
+        // Bands from gray up to blue, with headroom around 412 and 1701
+        assert!(svg.contains(">0</text>") && svg.contains(">2000</text>"));
+        assert!(!svg.contains(">2400</text>"));
+        assert!(svg.contains(r##"fill="#0000FF" fill-opacity="0.2""##));
+        assert!(!svg.contains("<polyline points=\"\""));
+
+        let empty = render_rating_graph(&[GraphSeries::from_results("bob".to_string(), &[])], 640, 360);
+        assert!(empty.contains("No rated contests"));
+    }
+}
@@ -0,0 +1,116 @@
+use anyhow::Result;
+use crate::domain::dto::{ClubStats, ColorCount, GradeStats, RatingStats};
+use crate::domain::entity::{ContestType, UserGrouping};
+use crate::domain::rating_color::{COLOR_WIDTH, RatingColor};
+
+/// Aggregates the ratings of active members for the statistics page.
+pub async fn get_club_stats<PR>(persist_repository: &PR, contest_type: ContestType) -> Result<ClubStats>
+where
+    PR: crate::domain::persist_repository::PersistRepository,
+{
+    let bands = persist_repository
+        .count_rated_users_by_band(contest_type, COLOR_WIDTH)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to count users by rating: {}", e))?;
+    let mut colors = RatingColor::ALL
+        .map(|color| ColorCount { color, count: 0 });
+    for (lower_bound, count) in bands {
+        // Bands above red's lower bound are all red
+        colors[RatingColor::from_rating(lower_bound) as usize].count += count;
+    }
+    let aggregates = |grouping| async move {
+        persist_repository
+            .get_rating_aggregates(contest_type, grouping)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to aggregate ratings: {}", e))
+    };
+    let all = aggregates(UserGrouping::All)
+        .await?
+        .into_iter()
+        .next()
+        .map(RatingStats::from)
+        .unwrap_or_default();
+    let grades = aggregates(UserGrouping::Grade)
+        .await?
+        .into_iter()
+        .map(|aggregate| GradeStats {
+            grade: aggregate.group.clone(),
+            stats: aggregate.into(),
+        })
+        .collect::<Vec<_>>();
+    let mut algo_team = RatingStats::default();
+    let mut others = RatingStats::default();
+    for aggregate in aggregates(UserGrouping::AlgoTeam).await? {
+        match aggregate.group.as_deref() {
+            Some("algoTeam") => algo_team = aggregate.into(),
+            _ => others = aggregate.into(),
+        }
+    }
+    Ok(ClubStats {
+        contest_type: contest_type.as_str().to_string(),
+        members: all.members,
+        linked: all.linked,
+        linked_share: if all.members > 0 { all.linked as f64 / all.members as f64 } else { 0.0 },
+        colors: colors.to_vec(),
+        grades,
+        algo_team,
+        others,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::dto::User;
+    use crate::domain::persist_repository::PersistRepository as _;
+    use crate::testing::in_memory_persist_repository::InMemoryPersistRepository;
+
+    fn user(name: &str, grade: &str, is_algo_team: bool, atcoder_rating: Option<i32>) -> User {
+        User {
+            trap_account_name: name.to_string(),
+            atcoder_account_name: atcoder_rating.map(|_| format!("{}_ac", name)),
+            atcoder_rating,
+            heuristic_rating: None,
+            is_algo_team: Some(is_algo_team),
+            is_active: Some(true),
+            grade: Some(grade.to_string()),
+            last_fetch_error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_club_stats() {
+        let persist_repository = InMemoryPersistRepository::default();
+        let inactive = User {
+            is_active: Some(false),
+            ..user("dave", "19B", true, Some(3000))
+        };
+        persist_repository
+            .set_users(vec![
+                user("alice", "23B", true, Some(412)),
+                user("bob", "23B", true, Some(1701)),
+                user("carol", "23B", false, Some(0)),
+                user("erin", "24B", false, Some(3300)),
+                user("frank", "24B", false, None),
+                inactive,
+            ])
+            .await
+            .unwrap();
+
+        let stats = get_club_stats(&persist_repository, ContestType::Algorithm).await.unwrap();
+        assert_eq!((stats.members, stats.linked), (5, 4));
+        assert_eq!(stats.linked_share, 0.8);
+        let count = |color| stats.colors[color as usize].count;
+        assert_eq!(stats.colors.len(), 8);
+        assert_eq!((count(RatingColor::Brown), count(RatingColor::Blue), count(RatingColor::Red)), (1, 1, 1));
+        assert_eq!(count(RatingColor::Gray), 0);
+
+        let grade = &stats.grades[0];
+        assert_eq!(grade.grade.as_deref(), Some("23B"));
+        assert_eq!((grade.stats.members, grade.stats.rated), (3, 2));
+        assert_eq!(grade.stats.median_rating, Some(1056.5));
+        assert_eq!(grade.stats.max_rating, Some(1701));
+        assert_eq!(stats.algo_team.members, 2);
+        assert_eq!(stats.others.mean_rating, Some(3300.0));
+    }
+}
@@ -1,57 +1,156 @@
-use std::{collections::HashMap, sync::Arc};
-use tokio::sync::Mutex;
+use std::{collections::{HashMap, HashSet}, sync::Arc, time::Duration};
 use anyhow::Result;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use crate::domain::dto::User;
+use crate::domain::entity::{ColorPromotion, ContestResult, ContestType, UpdateMode};
+use crate::domain::rating_color::RatingColor;
+use super::contest_report::get_contest_report;
+use super::digest::Digest;
+
+// Must fit in `users.last_fetch_error`
+const MAX_FETCH_ERROR_LENGTH: usize = 1024;
+// Contests whose results are still empty after this many days are given up on
+const MAX_RESULTS_DELAY_DAYS: i64 = 14;
+
+fn describe_failure(source: &str, reason: &str) -> String {
+    format!("{}: {}", source, reason)
+        .chars()
+        .take(MAX_FETCH_ERROR_LENGTH)
+        .collect()
+}
+
+/// Detects a move into a higher color. Members without an earlier rating, including those
+/// who were unrated with 0, are not promoted.
+fn color_promotion(
+    user: &User,
+    previous: Option<&User>,
+    contest_type: ContestType,
+    latest_contest: Option<&ContestResult>,
+) -> Option<ColorPromotion> {
+    let old_rating = previous?
+        .rating(contest_type)
+        .filter(|rating| *rating > 0)?;
+    let new_rating = user.rating(contest_type)?;
+    let old_color = RatingColor::from_rating(old_rating);
+    let new_color = RatingColor::from_rating(new_rating);
+    if new_color <= old_color {
+        return None;
+    }
+    Some(ColorPromotion {
+        trap_account_name: user.trap_account_name.clone(),
+        atcoder_account_name: user.atcoder_account_name.clone()?,
+        contest_type,
+        old_rating,
+        new_rating,
+        old_color,
+        new_color,
+        contest_name: latest_contest.map(|result| result.contest_name.clone()),
+    })
+}
 
+pub struct ScheduleConfig {
+    pub cron: String,
+    pub update_on_start: bool,
+    pub max_retries: u32,
+    pub retry_interval: Duration,
+}
 
 pub struct Updater<
     DU: crate::domain::detail_updater::DetailedInfoUpdater,
     AU: crate::domain::ac_account_updater::TrapMemberAcAccountUpdater,
     TR: crate::domain::traq_repository::TraqRepository,
     PR: crate::domain::persist_repository::PersistRepository,
+    CF: crate::domain::contest_results_fetcher::ContestResultsFetcher,
+    TB: crate::domain::traq_bot::TraqBot,
+    NT: crate::domain::notifier::Notifier,
 > {
     detail_updater: DU,
     account_updater: AU,
     traq_repository: TR,
     persist_repository: Arc<PR>,
+    contest_fetcher: CF,
+    mode: UpdateMode,
+    digest: Option<Digest<PR, TB>>,
+    notifier: Option<NT>,
+    update_lock: Mutex<()>,
 }
 
-impl <DU, AU, TR, PR> Updater<DU, AU, TR, PR>
+impl <DU, AU, TR, PR, CF, TB, NT> Updater<DU, AU, TR, PR, CF, TB, NT>
 where
     DU: crate::domain::detail_updater::DetailedInfoUpdater,
     AU: crate::domain::ac_account_updater::TrapMemberAcAccountUpdater,
     TR: crate::domain::traq_repository::TraqRepository,
     PR: crate::domain::persist_repository::PersistRepository,
+    CF: crate::domain::contest_results_fetcher::ContestResultsFetcher,
+    TB: crate::domain::traq_bot::TraqBot,
+    NT: crate::domain::notifier::Notifier,
 {
     pub fn new(
         detail_updater: DU,
         account_updater: AU,
         traq_repository: TR,
         persist_repository: Arc<PR>,
+        contest_fetcher: CF,
+        mode: UpdateMode,
     ) -> Self {
         Self {
             detail_updater,
             account_updater,
             traq_repository,
             persist_repository,
+            contest_fetcher,
+            mode,
+            digest: None,
+            notifier: None,
+            update_lock: Mutex::new(()),
         }
     }
 
-    pub async fn serve(self) -> Result<()> {
-        let scheduler = tokio_cron_scheduler::JobScheduler::new()
+    /// Posts `digest` after every successful update.
+    pub fn with_digest(mut self, digest: Digest<PR, TB>) -> Self {
+        self.digest = Some(digest);
+        self
+    }
+
+    /// Announces color promotions through `notifier` after every successful update.
+    pub fn with_notifier(mut self, notifier: NT) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Runs the update job on `config.cron` until `shutdown` is cancelled.
+    pub async fn serve(self: Arc<Self>, config: ScheduleConfig, shutdown: CancellationToken) -> Result<()> {
+        let mut scheduler = tokio_cron_scheduler::JobScheduler::new()
             .await
             .map_err(|e| anyhow::anyhow!("Failed to create scheduler: {}", e))?;
-        let updater = Arc::new(self);
+        // Seed the gauge so a restart does not look like the updates have never succeeded
+        match self.persist_repository.get_last_update_run().await {
+            Ok(Some(last_run)) => {
+                metrics::gauge!("update_last_success_timestamp_seconds").set(last_run.finished_at.timestamp() as f64);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to get last update run: {}", e),
+        }
+        let config = Arc::new(config);
+        if config.update_on_start {
+            let updater = self.clone();
+            let config = config.clone();
+            tokio::spawn(async move {
+                tracing::info!("Updating on start");
+                updater.update_with_retry(&config).await;
+            });
+        }
+        let updater = self.clone();
+        let job_config = config.clone();
         scheduler
             .add(
-                tokio_cron_scheduler::Job::new_async("0 0 4 * * Mon", move |_, _| {
+                tokio_cron_scheduler::Job::new_async(config.cron.as_str(), move |_, _| {
                     let updater = updater.clone();
-                    let future = Box::pin(async move {
-                        updater.update()
-                            .await
-                            .map_err(|e| anyhow::anyhow!("Failed to update: {}", e))
-                            .unwrap();
-                    });
-                    future
+                    let config = job_config.clone();
+                    Box::pin(async move {
+                        updater.update_with_retry(&config).await;
+                    })
                 })
                     .map_err(|e| anyhow::anyhow!("Failed to create job: {}", e))?
             )
@@ -61,10 +160,66 @@ where
             .start()
             .await
             .map_err(|e| anyhow::anyhow!("Failed to start scheduler: {}", e))?;
+        tracing::info!("Scheduled update job with cron expression {}", config.cron);
+        shutdown.cancelled().await;
+        tracing::info!("Shutting down scheduler");
+        scheduler
+            .shutdown()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to shut down scheduler: {}", e))?;
         Ok(())
     }
 
+    /// Runs `update`, retrying with exponential backoff. Failures are logged, never propagated.
+    pub async fn update_with_retry(&self, config: &ScheduleConfig) {
+        let Ok(_guard) = self.update_lock.try_lock() else {
+            tracing::warn!("Skipping update because another update is still running");
+            return;
+        };
+        let mut interval = config.retry_interval;
+        for attempt in 0..=config.max_retries {
+            let started = std::time::Instant::now();
+            let result = self.update().await;
+            let outcome = if result.is_ok() { "success" } else { "failure" };
+            metrics::histogram!("update_duration_seconds", "result" => outcome).record(started.elapsed().as_secs_f64());
+            match result {
+                Ok(()) => {
+                    metrics::gauge!("update_last_success_timestamp_seconds").set(chrono::Utc::now().timestamp() as f64);
+                    tracing::info!("Update finished");
+                    return;
+                }
+                Err(e) if attempt < config.max_retries => {
+                    tracing::error!(
+                        "Update failed (attempt {}/{}), retrying in {:?}: {}",
+                        attempt + 1,
+                        config.max_retries + 1,
+                        interval,
+                        e
+                    );
+                    tokio::time::sleep(interval).await;
+                    interval *= 2;
+                }
+                Err(e) => {
+                    tracing::error!("Update failed after {} attempts: {}", attempt + 1, e);
+                }
+            }
+        }
+    }
+
     pub async fn update(&self) -> Result<()> {
+        let run_at = chrono::Utc::now();
+        let previous_users = self.persist_repository
+            .get_users()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get previous users: {}", e))?
+            .into_iter()
+            .map(|user| {
+                (
+                    user.trap_account_name.clone(),
+                    user
+                )
+            })
+            .collect::<HashMap<_, _>>();
         let trap_members = self.traq_repository
             .get_members()
             .await
@@ -77,49 +232,128 @@ where
                 )
             })
             .collect::<HashMap<_, _>>();
-        let trap_members_with_ac_account = self.account_updater
+        let accounts = self.account_updater
             .get()
             .await
             .map_err(|e| anyhow::anyhow!("Failed to get members with ac account: {}", e))?;
-        let atcoder_usernames = trap_members_with_ac_account
+        let trap_members_with_ac_account = accounts.fetched;
+        let last_run = self.persist_repository
+            .get_last_update_run()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get last update run: {}", e))?;
+        if let Some(last_run) = &last_run {
+            tracing::info!(
+                "Last update ran in {} mode from {} to {}",
+                last_run.mode,
+                last_run.started_at,
+                last_run.finished_at
+            );
+        }
+        // Without a previous run there is nothing to continue from, so fetch everyone
+        let mode = match &last_run {
+            Some(_) => self.mode,
+            None => UpdateMode::User,
+        };
+        tracing::info!("Updating in {} mode", mode.as_str());
+        let backfill_usernames = trap_members_with_ac_account
             .iter()
             .filter_map(|member| {
-                member.ac_account_name.clone()
+                let username = member.ac_account_name.as_ref()?;
+                let needs_backfill = mode == UpdateMode::User || previous_users
+                    .get(&member.trap_account_name)
+                    .is_none_or(|user| {
+                        user.atcoder_account_name.as_ref() != Some(username)
+                            || user.atcoder_rating.is_none()
+                            || user.last_fetch_error.is_some()
+                    });
+                needs_backfill.then(|| username.clone())
             })
+            .collect::<HashSet<_>>();
+        let tracked_usernames = trap_members_with_ac_account
+            .iter()
+            .filter_map(|member| member.ac_account_name.clone())
+            .filter(|username| !backfill_usernames.contains(username))
             .collect::<Vec<_>>();
-        let detailed_infos = self.detail_updater
-            .get(atcoder_usernames)
+        let details = self.detail_updater
+            .get(backfill_usernames.into_iter().collect())
             .await
             .map_err(|e| anyhow::anyhow!("Failed to get detailed info: {}", e))?;
+        let detailed_infos = details.fetched;
+        let detail_failures = details.failures
+            .into_iter()
+            .map(|failure| (failure.key, failure.reason))
+            .collect::<HashMap<_, _>>();
+        for (username, info) in detailed_infos.iter() {
+            self.persist_repository
+                .set_contest_results(username, ContestType::Algorithm, info.algo_rating.clone())
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to set algorithm contest results: {}", e))?;
+            self.persist_repository
+                .set_contest_results(username, ContestType::Heuristic, info.heur_rating.clone())
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to set heuristic contest results: {}", e))?;
+        }
+        let (latest_results, finished_contests) = match (mode, &last_run) {
+            (UpdateMode::Contest, Some(last_run)) => self
+                .apply_finished_contests(last_run.started_at, run_at, tracked_usernames)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to apply finished contests: {}", e))?,
+            _ => (HashMap::new(), vec![]),
+        };
         let mut users = vec![];
+        let mut promotions = vec![];
         for member in trap_members_with_ac_account {
             let trap_member = trap_members
                 .get(&member.trap_account_name);
+            let previous = previous_users
+                .get(&member.trap_account_name)
+                .filter(|user| user.atcoder_account_name == member.ac_account_name);
+            let fetch_error = member.ac_account_name
+                .as_ref()
+                .and_then(|username| detail_failures.get(username))
+                .map(|reason| describe_failure("AtCoder", reason));
             let detailed_info = member.ac_account_name
                 .as_ref()
-                .map(|username| {
+                .and_then(|username| {
                     detailed_infos.get(username)
-                })
-                .flatten();
-            let user = crate::domain::dto::User {
+                });
+            let latest_contest = |contest_type| match detailed_info {
+                Some(info) => match contest_type {
+                    ContestType::Algorithm => info.algo_rating.last(),
+                    ContestType::Heuristic => info.heur_rating.last(),
+                },
+                None => member.ac_account_name
+                    .as_ref()
+                    .and_then(|username| latest_results.get(&(username.clone(), contest_type))),
+            };
+            let latest_contests = [ContestType::Algorithm, ContestType::Heuristic]
+                .map(|contest_type| (contest_type, latest_contest(contest_type)));
+            let (atcoder_rating, heuristic_rating) = match (detailed_info, &fetch_error) {
+                (Some(info), _) => (
+                    Some(info.algo_rating.last().map_or(0, |result| result.new_rating)),
+                    Some(info.heur_rating.last().map_or(0, |result| result.new_rating)),
+                ),
+                // Keep the last known ratings unless a newly finished contest changed them
+                (None, _) => {
+                    let latest_rating = |contest_type| {
+                        member.ac_account_name
+                            .as_ref()
+                            .and_then(|username| latest_results.get(&(username.clone(), contest_type)))
+                            .map(|result: &ContestResult| result.new_rating)
+                    };
+                    (
+                        latest_rating(ContestType::Algorithm)
+                            .or(previous.and_then(|user| user.atcoder_rating)),
+                        latest_rating(ContestType::Heuristic)
+                            .or(previous.and_then(|user| user.heuristic_rating)),
+                    )
+                }
+            };
+            let user = User {
                 trap_account_name: member.trap_account_name,
                 atcoder_account_name: member.ac_account_name,
-                atcoder_rating: detailed_info
-                    .map(|info| {
-                        if info.algo_rating.is_empty() {
-                            0
-                        } else {
-                            info.algo_rating[info.algo_rating.len() - 1].new_rating
-                        }
-                    }),
-                heuristic_rating: detailed_info
-                    .map(|info| {
-                        if info.heur_rating.is_empty() {
-                            0
-                        } else {
-                            info.heur_rating[info.heur_rating.len() - 1].new_rating
-                        }
-                    }),
+                atcoder_rating,
+                heuristic_rating,
                 is_algo_team: trap_member
                     .map(|member| {
                         member.is_algo_team
@@ -129,17 +363,429 @@ where
                         member.is_active
                     }),
                 grade: trap_member
-                    .map(|member| {
+                    .and_then(|member| {
                         member.grade.clone()
-                    })
-                    .flatten(),
+                    }),
+                last_fetch_error: fetch_error,
             };
+            promotions.extend(latest_contests.into_iter().filter_map(|(contest_type, contest)| {
+                color_promotion(&user, previous, contest_type, contest)
+            }));
             users.push(user);
         }
+        for failure in accounts.failures.iter() {
+            // The AtCoder link is unknown, so keep whatever was stored before
+            let trap_member = trap_members.get(&failure.key);
+            let previous = previous_users.get(&failure.key);
+            users.push(User {
+                trap_account_name: failure.key.clone(),
+                atcoder_account_name: previous.and_then(|user| user.atcoder_account_name.clone()),
+                atcoder_rating: previous.and_then(|user| user.atcoder_rating),
+                heuristic_rating: previous.and_then(|user| user.heuristic_rating),
+                is_algo_team: trap_member.map(|member| member.is_algo_team),
+                is_active: trap_member.map(|member| member.is_active),
+                grade: trap_member.and_then(|member| member.grade.clone()),
+                last_fetch_error: Some(describe_failure("traPortfolio", &failure.reason)),
+            });
+        }
+        let failed_count = users
+            .iter()
+            .filter(|user| user.last_fetch_error.is_some())
+            .count();
+        if failed_count > 0 {
+            tracing::warn!("Failed to fetch {} of {} users", failed_count, users.len());
+            for user in users.iter() {
+                if let Some(error) = &user.last_fetch_error {
+                    tracing::warn!("{}: {}", user.trap_account_name, error);
+                }
+            }
+        }
+        let fetched_users = users
+            .iter()
+            .filter(|user| user.last_fetch_error.is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        self.persist_repository
+            .add_rating_snapshots(run_at, &fetched_users)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to add rating snapshots: {}", e))?;
         self.persist_repository
             .set_users(users)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to set users: {}", e))?;
+        self.persist_repository
+            .add_update_run(mode, run_at, chrono::Utc::now())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to add update run: {}", e))?;
+        if let Some(digest) = &self.digest {
+            // The update itself succeeded, so retrying it would not help
+            if let Err(e) = digest.post_for_last_run().await {
+                tracing::error!("Failed to post digest: {}", e);
+            }
+        }
+        if let Some(notifier) = &self.notifier {
+            tracing::info!("{} members reached a new color", promotions.len());
+            if let Err(e) = notifier.notify_color_promotions(&promotions).await {
+                tracing::error!("Failed to notify color promotions: {}", e);
+            }
+            for contest_screen_name in finished_contests {
+                let report = match get_contest_report(self.persist_repository.as_ref(), &contest_screen_name).await {
+                    Ok(Some(report)) => report,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        tracing::error!("Failed to get report of {}: {}", contest_screen_name, e);
+                        continue;
+                    }
+                };
+                if let Err(e) = notifier.notify_contest_report(&report).await {
+                    tracing::error!("Failed to notify report of {}: {}", contest_screen_name, e);
+                }
+            }
+        }
         Ok(())
     }
+
+    /// Stores the results of contests that finished in `(since, until]` for the given
+    /// AtCoder users, along with earlier contests whose results were not out yet.
+    /// Returns each user's latest rated result per contest type and the screen names of
+    /// the contests applied.
+    async fn apply_finished_contests(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        until: chrono::DateTime<chrono::Utc>,
+        usernames: Vec<String>,
+    ) -> Result<(HashMap<(String, ContestType), ContestResult>, Vec<String>)> {
+        let finished = self.contest_fetcher
+            .get_finished_contests(since, until)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get finished contests: {}", e))?;
+        tracing::info!("{} contests finished since {}", finished.len(), since);
+        self.persist_repository
+            .add_pending_contests(&finished)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to add pending contests: {}", e))?;
+        let contests = self.persist_repository
+            .get_pending_contests(until - chrono::Duration::days(MAX_RESULTS_DELAY_DAYS))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get pending contests: {}", e))?;
+        // AtCoder usernames are case-insensitive
+        let usernames = usernames
+            .into_iter()
+            .map(|username| (username.to_lowercase(), username))
+            .collect::<HashMap<_, _>>();
+        let mut updated = HashSet::new();
+        let mut screen_names = vec![];
+        for contest in contests {
+            let results = self.contest_fetcher
+                .get_results(&contest)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to get results of {}: {}", contest.contest_id, e))?;
+            // The results are empty until the ratings are out, which takes days for AHCs
+            if results.is_empty() {
+                tracing::info!("Results of {} are not out yet", contest.contest_id);
+                continue;
+            }
+            screen_names.push(contest.contest_id.clone());
+            for (username, result) in results {
+                let Some(username) = usernames.get(&username.to_lowercase()) else {
+                    continue;
+                };
+                self.persist_repository
+                    .set_contest_results(username, contest.contest_type, vec![result])
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to set contest results: {}", e))?;
+                updated.insert((username.clone(), contest.contest_type));
+            }
+            self.persist_repository
+                .mark_contest_applied(&contest.contest_id, until)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to mark {} as applied: {}", contest.contest_id, e))?;
+        }
+        // A contest applied late may be older than one applied before, so read back the history
+        let mut latest_results = HashMap::new();
+        for (username, contest_type) in updated {
+            let latest = self.persist_repository
+                .get_contest_results(&username, contest_type)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to get contest results: {}", e))?
+                .into_iter()
+                .rfind(|result| result.is_rated);
+            if let Some(result) = latest {
+                latest_results.insert((username, contest_type), result);
+            }
+        }
+        Ok((latest_results, screen_names))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::persist_repository::PersistRepository as _;
+    use crate::infra::{
+        ac_account_updater::TrapMemberAcAccountUpdaterImpl,
+        contest_results_fetcher::ContestResultsFetcherImpl,
+        detail_updater::DetailUpdaterImpl,
+        traq_notifier::{TraqNotifierChannels, TraqNotifierImpl},
+        traq_repository::TraqRepositoryImpl,
+    };
+    use crate::testing::{
+        fake_server::{FakeServer, FakeUpstreams, atcoder_router, contest_list_entry},
+        in_memory_persist_repository::InMemoryPersistRepository,
+        recording_traq_bot::RecordingTraqBot,
+    };
+
+    type TestUpdater = Updater<
+        DetailUpdaterImpl,
+        TrapMemberAcAccountUpdaterImpl,
+        TraqRepositoryImpl,
+        InMemoryPersistRepository,
+        ContestResultsFetcherImpl,
+        RecordingTraqBot,
+        TraqNotifierImpl<RecordingTraqBot>,
+    >;
+
+    fn updater(
+        upstreams: &FakeUpstreams,
+        persist_repository: Arc<InMemoryPersistRepository>,
+        mode: UpdateMode,
+    ) -> TestUpdater {
+        Updater::new(
+            DetailUpdaterImpl::new(upstreams.atcoder.config()),
+            TrapMemberAcAccountUpdaterImpl::new(upstreams.traportfolio.config()),
+            TraqRepositoryImpl::new(upstreams.traq.config(), "token".to_string()),
+            persist_repository,
+            ContestResultsFetcherImpl::new(upstreams.atcoder.config(), upstreams.contest_list.config()),
+            mode,
+        )
+    }
+
+    async fn user(persist_repository: &InMemoryPersistRepository, name: &str) -> User {
+        persist_repository
+            .get_user(name)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("{} not found", name))
+    }
+
+    #[tokio::test]
+    async fn test_update_user_mode() {
+        let upstreams = FakeUpstreams::start(vec![]).await;
+        let persist_repository = Arc::new(InMemoryPersistRepository::default());
+        updater(&upstreams, persist_repository.clone(), UpdateMode::User)
+            .update()
+            .await
+            .unwrap();
+
+        let alice = user(&persist_repository, "alice").await;
+        assert_eq!(alice.atcoder_account_name.as_deref(), Some("alice_ac"));
+        assert_eq!(alice.atcoder_rating, Some(412));
+        assert_eq!(alice.heuristic_rating, Some(693));
+        assert_eq!(alice.is_algo_team, Some(true));
+        assert_eq!(alice.grade.as_deref(), Some("23B"));
+        assert_eq!(alice.last_fetch_error, None);
+        let bob = user(&persist_repository, "bob").await;
+        assert_eq!(bob.atcoder_rating, Some(1623));
+        assert_eq!(bob.heuristic_rating, Some(0));
+        // A missing AtCoder account does not stop everyone else from being updated
+        let carol = user(&persist_repository, "carol").await;
+        assert_eq!(carol.atcoder_rating, None);
+        assert!(carol.last_fetch_error.unwrap().starts_with("AtCoder"));
+        let dave = user(&persist_repository, "dave").await;
+        assert_eq!(dave.atcoder_account_name, None);
+        assert_eq!(dave.is_active, Some(false));
+        let eve = user(&persist_repository, "eve").await;
+        assert!(eve.last_fetch_error.unwrap().starts_with("traPortfolio"));
+
+        let history = persist_repository
+            .get_contest_results("alice_ac", ContestType::Algorithm)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        let state = persist_repository.state.lock().unwrap();
+        assert_eq!(state.rating_snapshots.len(), 3);
+        assert_eq!(state.update_runs.len(), 1);
+        assert_eq!(state.update_runs[0].mode, "user");
+    }
+
+    #[tokio::test]
+    async fn test_update_records_rating_snapshots() {
+        let upstreams = FakeUpstreams::start(vec![]).await;
+        let persist_repository = Arc::new(InMemoryPersistRepository::default());
+        let updater = updater(&upstreams, persist_repository.clone(), UpdateMode::User);
+        let started = chrono::Utc::now();
+        updater.update().await.unwrap();
+        let between = chrono::Utc::now();
+        updater.update().await.unwrap();
+        let finished = chrono::Utc::now();
+
+        let snapshots = persist_repository
+            .get_rating_snapshots("alice", started, finished)
+            .await
+            .unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert!(snapshots[0].taken_at <= snapshots[1].taken_at);
+        assert_eq!(snapshots[1].atcoder_rating, Some(412));
+        let snapshots = persist_repository
+            .get_rating_snapshots("alice", between, finished)
+            .await
+            .unwrap();
+        assert_eq!(snapshots.len(), 1);
+        let snapshots = persist_repository
+            .get_rating_snapshots("alice", started - chrono::Duration::days(1), started)
+            .await
+            .unwrap();
+        assert!(snapshots.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_contest_mode() {
+        let now = chrono::Utc::now();
+        let upstreams = FakeUpstreams::start(vec![
+            contest_list_entry("abc400", now - chrono::Duration::hours(1), " ~ 1999"),
+        ])
+            .await;
+        let persist_repository = Arc::new(InMemoryPersistRepository::default());
+        let traq_bot = Arc::new(RecordingTraqBot::default());
+        let updater = updater(&upstreams, persist_repository.clone(), UpdateMode::Contest)
+            .with_digest(Digest::new(persist_repository.clone(), traq_bot.clone(), "channel".to_string()))
+            .with_notifier(TraqNotifierImpl::new(traq_bot.clone(), TraqNotifierChannels {
+                contest_reports: Some("results".to_string()),
+                ..Default::default()
+            }));
+        // The first run has nothing to continue from and falls back to fetching every user
+        updater.update().await.unwrap();
+        {
+            let mut state = persist_repository.state.lock().unwrap();
+            assert_eq!(state.update_runs[0].mode, "user");
+            state.update_runs[0].started_at = now - chrono::Duration::days(1);
+        }
+        updater.update().await.unwrap();
+
+        let bob = user(&persist_repository, "bob").await;
+        assert_eq!(bob.atcoder_rating, Some(1701));
+        assert_eq!(bob.heuristic_rating, Some(0));
+        let alice = user(&persist_repository, "alice").await;
+        assert_eq!(alice.atcoder_rating, Some(412));
+        let history = persist_repository
+            .get_contest_results("bob_ac", ContestType::Algorithm)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].contest_screen_name, "abc400.contest.atcoder.jp");
+        let state = persist_repository.state.lock().unwrap();
+        assert_eq!(state.update_runs.last().unwrap().mode, "contest");
+
+        // One digest per run, the second one comparing against the first
+        let messages = traq_bot.messages();
+        let digests = messages
+            .iter()
+            .filter(|(channel_id, _)| channel_id == "channel")
+            .collect::<Vec<_>>();
+        assert_eq!(digests.len(), 2);
+        assert!(!digests[0].1.contains("Biggest gainers"));
+        assert!(digests[1].1.contains("| bob | algorithm | 1623 → 1701 | +78 |"));
+        // The contest applied in the second run is reported
+        let reports = messages
+            .iter()
+            .filter(|(channel_id, _)| channel_id == "results")
+            .collect::<Vec<_>>();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].1.contains("| bob | bob_ac |"));
+    }
+
+    #[tokio::test]
+    async fn test_update_contest_mode_waits_for_results() {
+        let now = chrono::Utc::now();
+        let upstreams = FakeUpstreams::start(vec![
+            contest_list_entry("abc400", now - chrono::Duration::hours(1), " ~ 1999"),
+        ])
+            .await;
+        // The results come back empty until the ratings are out
+        let is_out = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let atcoder = {
+            let is_out = is_out.clone();
+            FakeServer::start(atcoder_router().route(
+                "/contests/abc400/results/json",
+                axum::routing::get(move || {
+                    let is_out = is_out.load(std::sync::atomic::Ordering::SeqCst);
+                    async move {
+                        if is_out {
+                            include_str!("../testing/fixtures/atcoder/results_abc400.json")
+                        } else {
+                            "[]"
+                        }
+                    }
+                }),
+            ))
+                .await
+        };
+        let persist_repository = Arc::new(InMemoryPersistRepository::default());
+        let updater: TestUpdater = Updater::new(
+            DetailUpdaterImpl::new(upstreams.atcoder.config()),
+            TrapMemberAcAccountUpdaterImpl::new(upstreams.traportfolio.config()),
+            TraqRepositoryImpl::new(upstreams.traq.config(), "token".to_string()),
+            persist_repository.clone(),
+            ContestResultsFetcherImpl::new(atcoder.config(), upstreams.contest_list.config()),
+            UpdateMode::Contest,
+        );
+        updater.update().await.unwrap();
+        persist_repository.state.lock().unwrap().update_runs[0].started_at = now - chrono::Duration::days(1);
+        updater.update().await.unwrap();
+        assert_eq!(user(&persist_repository, "bob").await.atcoder_rating, Some(1623));
+
+        // The next run starts after abc400 ended but still picks it up
+        is_out.store(true, std::sync::atomic::Ordering::SeqCst);
+        updater.update().await.unwrap();
+        assert_eq!(user(&persist_repository, "bob").await.atcoder_rating, Some(1701));
+        let state = persist_repository.state.lock().unwrap();
+        assert_eq!(state.applied_contests.len(), 1);
+        assert!(state.applied_contests[0].1.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_update_notifies_color_promotions() {
+        let upstreams = FakeUpstreams::start(vec![]).await;
+        let persist_repository = Arc::new(InMemoryPersistRepository::default());
+        let previous = |name: &str, atcoder_account_name: &str, atcoder_rating| User {
+            trap_account_name: name.to_string(),
+            atcoder_account_name: Some(atcoder_account_name.to_string()),
+            atcoder_rating: Some(atcoder_rating),
+            heuristic_rating: Some(0),
+            is_algo_team: Some(true),
+            is_active: Some(true),
+            grade: None,
+            last_fetch_error: None,
+        };
+        persist_repository
+            .set_users(vec![
+                previous("alice", "alice_ac", 350),
+                // Same color as before
+                previous("bob", "bob_ac", 1600),
+                // A different account is not a promotion
+                previous("carol", "old_carol_ac", 0),
+            ])
+            .await
+            .unwrap();
+        let traq_bot = Arc::new(RecordingTraqBot::default());
+        updater(&upstreams, persist_repository.clone(), UpdateMode::User)
+            .with_notifier(TraqNotifierImpl::new(traq_bot.clone(), TraqNotifierChannels {
+                color_promotions: Some("channel".to_string()),
+                ..Default::default()
+            }))
+            .update()
+            .await
+            .unwrap();
+
+        let messages = traq_bot.messages();
+        assert_eq!(messages.len(), 1);
+        let lines = messages[0].1
+            .lines()
+            .filter(|line| line.starts_with("- "))
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1, "{:?}", lines);
+        assert!(lines[0].starts_with("- @alice (alice_ac) algorithm: gray → **brown** (350 → 412) in AtCoder Beginner Contest 301"));
+        // Alice's first heuristic rating is not a promotion from gray
+        assert!(!lines.iter().any(|line| line.contains("(0 → 693)")));
+    }
 }